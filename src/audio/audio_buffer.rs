@@ -3,6 +3,9 @@ use winit::window::Window;
 use crate::math::Cplx;
 use crate::math::interpolate::decay;
 
+#[cfg(feature = "simd")]
+use crate::math::simd;
+
 const SILENCE_LIMIT: f32 = 0.0001;
 const AMP_PERSIST_LIMIT: f32 = 0.01;
 const REACT_FACTOR: f32 = 0.98;
@@ -10,6 +13,13 @@ const REACT_FACTOR: f32 = 0.98;
 const BUFFER_CAPACITY: usize = 1 << 16;
 const BUFFER_MASK: usize = BUFFER_CAPACITY - 1;
 
+/// Where visualizers read from, moved along by `autoslide`.
+#[derive(Clone, Copy)]
+pub struct ReadCursor {
+    readend: usize,
+    rotatessincelastwrite: usize,
+}
+
 pub struct AudioBuffer {
     data: [Cplx; BUFFER_CAPACITY],
 
@@ -52,6 +62,13 @@ impl AudioBuffer {
         }
     }
 
+    /// Forgets all samples and levels, keeping the window to wake up.
+    pub fn clear(&mut self) {
+        let window = self.window;
+        *self = Self::new();
+        self.window = window;
+    }
+
     pub fn init_realtime_wakeup(&mut self, w: &'static dyn Window) {
         if self.window.is_some() {
             panic!("Already initialized!");
@@ -68,6 +85,11 @@ impl AudioBuffer {
         self.silent
     }
 
+    /// Recent peak of the input, before normalizing.
+    pub fn level(&self) -> f32 {
+        self.max
+    }
+
     pub fn read_from_input(&mut self, in_buffer: &[f32]) {
         let copysize = in_buffer.len() / 2;
 
@@ -98,6 +120,18 @@ impl AudioBuffer {
     fn post_process(&mut self) {
         let oldwriteend = (self.writeend - self.lastinputsize) & BUFFER_MASK;
         let (left, right) = self.data.split_at_mut(oldwriteend);
+
+        #[cfg(feature = "simd")]
+        let (right, left) = {
+            let rlen = right.len().min(self.lastinputsize);
+            let llen = self.lastinputsize - rlen;
+            (&mut right[..rlen], &mut left[..llen])
+        };
+
+        #[cfg(feature = "simd")]
+        let max = simd::max_abs(right).max(simd::max_abs(left));
+
+        #[cfg(not(feature = "simd"))]
         let max = right
             .iter()
             .chain(left.iter())
@@ -120,6 +154,13 @@ impl AudioBuffer {
         if self.normalize {
             let scale: f32 = 1.0 / self.max.max(AMP_PERSIST_LIMIT);
 
+            #[cfg(feature = "simd")]
+            {
+                simd::scale(right, scale);
+                simd::scale(left, scale);
+            }
+
+            #[cfg(not(feature = "simd"))]
             right
                 .iter_mut()
                 .chain(left.iter_mut())
@@ -145,6 +186,19 @@ impl AudioBuffer {
         self.data.len()
     }
 
+    pub fn cursor(&self) -> ReadCursor {
+        ReadCursor {
+            readend: self.readend,
+            rotatessincelastwrite: self.rotatessincelastwrite,
+        }
+    }
+
+    /// Lets several visualizers read the same samples in one frame.
+    pub fn set_cursor(&mut self, c: ReadCursor) {
+        self.readend = c.readend;
+        self.rotatessincelastwrite = c.rotatessincelastwrite;
+    }
+
     pub fn autoslide(&mut self) {
         let diff = self.writeend.saturating_sub(self.readend);
         let diff = diff / (self.rotatessincewrite + 4);
@@ -157,3 +211,64 @@ impl AudioBuffer {
         self.data[i]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::hint::black_box;
+    use std::time::Instant;
+
+    fn chunk(len: usize, seed: usize) -> Vec<f32> {
+        (0..len * 2)
+            .map(|i| ((i + seed) as f32 * 0.37).sin() * (0.2 + (seed % 7) as f32 * 0.1))
+            .collect()
+    }
+
+    /// The normalizing in `post_process`, written out one sample at a time.
+    #[test]
+    fn post_process_matches_scalar() {
+        let mut buf = Box::new(AudioBuffer::new());
+        let mut max = 0.0f32;
+
+        // Long enough to wrap around the end of the ring.
+        for (n, len) in [1600, 333, 40000, 25000, 7, 1601].into_iter().enumerate() {
+            let input = chunk(len, n);
+            let start = buf.writeend;
+
+            buf.read_from_input(&input);
+
+            let peak = input.iter().fold(0.0f32, |a, s| a.max(s.abs()));
+            max = decay(max, peak, REACT_FACTOR);
+            let scale = 1.0 / max.max(AMP_PERSIST_LIMIT);
+
+            assert_eq!(buf.level(), max);
+
+            for (i, s) in input.chunks_exact(2).enumerate() {
+                let c = buf.data[(start + i) & BUFFER_MASK];
+                assert!((c.0 - s[0] * scale).abs() <= 1e-6);
+                assert!((c.1 - s[1] * scale).abs() <= 1e-6);
+            }
+        }
+    }
+
+    /// Timings, not a check. Compare
+    /// `cargo test --release post_process_timing -- --ignored --nocapture`
+    /// with and without `--features simd`.
+    #[test]
+    #[ignore]
+    fn post_process_timing() {
+        let mut buf = Box::new(AudioBuffer::new());
+        let input = chunk(4096, 3);
+
+        let runs = 1 << 14;
+        let start = Instant::now();
+
+        for _ in 0..runs {
+            buf.read_from_input(black_box(&input));
+        }
+
+        let ns = start.elapsed().as_nanos() as f64 / runs as f64;
+        println!("post_process 4096: {ns:>8.0} ns");
+    }
+}
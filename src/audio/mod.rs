@@ -4,15 +4,24 @@ use cpal::traits::{DeviceTrait, HostTrait};
 pub mod audio_buffer;
 pub(crate) use audio_buffer::AudioBuffer;
 
+pub mod wav;
+
 use std::cell::Cell;
 use std::ops::*;
-use std::sync::{Mutex, MutexGuard};
+use std::sync::{Mutex, MutexGuard, OnceLock};
 
 pub(crate) fn get_buf<'a>() -> MutexGuard<'a, AudioBuffer> {
     static BUFFER: Mutex<AudioBuffer> = Mutex::new(AudioBuffer::new());
     BUFFER.lock().unwrap()
 }
 
+static DEVICE_NAME: OnceLock<String> = OnceLock::new();
+
+/// Name of the device `get_source` captures from.
+pub fn device_name() -> &'static str {
+    DEVICE_NAME.get().map_or("<Unknown device>", String::as_str)
+}
+
 #[cfg(target_os = "linux")]
 pub fn get_device_linux() -> Device {
     cpal::default_host()
@@ -37,14 +46,11 @@ pub fn get_source() -> cpal::Stream {
         .expect("error while querying configs")
         .config();
 
-    crate::data::log::info!(
-        "Using {}",
-        device
-            .description()
-            .as_ref()
-            .map(|v| v.name())
-            .unwrap_or("<Unknown device>")
-    );
+    if let Ok(description) = device.description() {
+        let _ = DEVICE_NAME.set(description.name().to_string());
+    }
+
+    crate::data::log::info!("Using {}", device_name());
 
     device
         .build_input_stream(
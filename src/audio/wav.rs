@@ -0,0 +1,127 @@
+use std::path::Path;
+
+use crate::data::log::error;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// A decoded WAV file, downmixed or upmixed to interleaved stereo.
+pub struct Wav {
+    pub sample_rate: u32,
+    pub samples: Vec<f32>,
+}
+
+fn u16_at(b: &[u8], i: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(b.get(i..i + 2)?.try_into().ok()?))
+}
+
+fn u32_at(b: &[u8], i: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(b.get(i..i + 4)?.try_into().ok()?))
+}
+
+fn decode_sample(s: &[u8], format: u16) -> f32 {
+    match (format, s.len()) {
+        (FORMAT_PCM, 1) => (s[0] as f32 - 128.0) / 128.0,
+        (FORMAT_PCM, 2) => i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0,
+        (FORMAT_PCM, 3) => i32::from_le_bytes([0, s[0], s[1], s[2]]) as f32 / 2147483648.0,
+        (FORMAT_PCM, 4) => i32::from_le_bytes([s[0], s[1], s[2], s[3]]) as f32 / 2147483648.0,
+        (FORMAT_FLOAT, 4) => f32::from_le_bytes([s[0], s[1], s[2], s[3]]),
+        (FORMAT_FLOAT, 8) => f64::from_le_bytes(s.try_into().unwrap()) as f32,
+        _ => 0.0,
+    }
+}
+
+impl Wav {
+    /// Supports 8 to 32 bit integer and 32 or 64 bit float PCM.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.get(0..4) != Some(b"RIFF") || bytes.get(8..12) != Some(b"WAVE") {
+            error!("Not a WAV file.");
+            return None;
+        }
+
+        let mut fmt = None;
+        let mut data = None;
+        let mut i = 12;
+
+        while let Some(len) = u32_at(bytes, i + 4) {
+            let start = i + 8;
+            let end = (start + len as usize).min(bytes.len());
+
+            match &bytes[i..i + 4] {
+                b"fmt " => fmt = Some(&bytes[start..end]),
+                b"data" => data = Some(&bytes[start..end]),
+                _ => {}
+            }
+
+            // Chunks are padded to an even length.
+            i = start + len as usize + (len as usize & 1);
+        }
+
+        let (Some(fmt), Some(data)) = (fmt, data) else {
+            error!("WAV file is missing its format or data.");
+            return None;
+        };
+
+        let mut format = u16_at(fmt, 0)?;
+        let channels = u16_at(fmt, 2)? as usize;
+        let sample_rate = u32_at(fmt, 4)?;
+        let bits = u16_at(fmt, 14)?;
+
+        // The actual format is the start of the sub format GUID.
+        if format == FORMAT_EXTENSIBLE {
+            format = u16_at(fmt, 24)?;
+        }
+
+        let width = bits as usize / 8;
+
+        let supported = match format {
+            FORMAT_PCM => (1..=4).contains(&width),
+            FORMAT_FLOAT => width == 4 || width == 8,
+            _ => false,
+        };
+
+        if !supported || channels == 0 || sample_rate == 0 {
+            error!(
+                "Unsupported WAV format {} with {} bits and {} channels.",
+                format, bits, channels
+            );
+            return None;
+        }
+
+        // Mono is duplicated, anything past the first two channels is dropped.
+        let right = if channels > 1 { width } else { 0 };
+
+        let samples = data
+            .chunks_exact(width * channels)
+            .flat_map(|frame| {
+                let l = decode_sample(&frame[..width], format);
+                let r = decode_sample(&frame[right..right + width], format);
+                [l, r]
+            })
+            .collect();
+
+        Some(Self {
+            sample_rate,
+            samples,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Option<Self> {
+        let path = path.as_ref();
+
+        let bytes = std::fs::read(path)
+            .inspect_err(|e| {
+                let path = path.display();
+                error!("Failed to read {}: {}", path, e)
+            })
+            .ok()?;
+
+        Self::decode(&bytes)
+    }
+
+    /// Number of stereo frames.
+    pub fn frame_count(&self) -> usize {
+        self.samples.len() / 2
+    }
+}
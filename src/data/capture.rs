@@ -0,0 +1,190 @@
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::graphics::{Painter, gif, image::Image};
+
+/// Frames closer together than this are dropped from recordings. GIF
+/// delays count in hundredths of a second and most viewers slow
+/// down anything faster than 50 fps.
+const GIF_FRAME_INTERVAL: Duration = Duration::from_millis(20);
+
+struct Recording {
+    /// Each frame with its time since the recording started.
+    frames: Vec<(Image, Duration)>,
+    started: Instant,
+    length: Duration,
+}
+
+/// Screenshots and GIF recordings of the canvas, taken at the logical
+/// resolution after post effects but before the name overlay.
+pub struct Capture {
+    screenshot: bool,
+    recording: Option<Recording>,
+
+    /// Either `png` or `qoi`.
+    pub screenshot_format: &'static str,
+    pub gif_length: Duration,
+}
+
+#[cfg(not(target_os = "windows"))]
+fn pictures_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_PICTURES_DIR") {
+        return Some(dir.into());
+    }
+
+    let config = xdg::BaseDirectories::new().get_config_home()?;
+    let dirs = std::fs::read_to_string(config.join("user-dirs.dirs")).ok()?;
+
+    let dir = dirs
+        .lines()
+        .find_map(|l| l.strip_prefix("XDG_PICTURES_DIR="))?
+        .trim()
+        .trim_matches('"');
+
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(dir.replace("$HOME", &home)))
+}
+
+/// The XDG pictures directory, or the cache directory if there is none.
+#[cfg(not(target_os = "windows"))]
+fn capture_dir() -> Option<PathBuf> {
+    pictures_dir().or_else(|| xdg::BaseDirectories::new().get_cache_home())
+}
+
+#[cfg(target_os = "windows")]
+fn capture_dir() -> Option<PathBuf> {
+    std::env::var_os("USERPROFILE").map(|p| PathBuf::from(p).join("Pictures"))
+}
+
+/// A new file name in `<capture dir>/coffeevis/`, falling
+/// back to the working directory.
+fn capture_path(extension: &str) -> Option<PathBuf> {
+    let dir = capture_dir().unwrap_or_default().join("coffeevis");
+
+    std::fs::create_dir_all(&dir)
+        .inspect_err(|e| {
+            let dir = dir.display();
+            error!("Failed to create {}: {}", dir, e)
+        })
+        .ok()?;
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+
+    Some(dir.join(format!("coffeevis-{millis}.{extension}")))
+}
+
+impl Capture {
+    pub fn new() -> Self {
+        Self {
+            screenshot: false,
+            recording: None,
+            screenshot_format: "png",
+            gif_length: crate::data::DEFAULT_GIF_LENGTH,
+        }
+    }
+
+    /// Saves the next frame.
+    pub fn request_screenshot(&mut self) {
+        self.screenshot = true;
+    }
+
+    /// Starts recording the next `gif_length`, or stops early.
+    pub fn toggle_recording(&mut self) {
+        if self.recording.is_some() {
+            self.finish_recording();
+            return;
+        }
+
+        info!("Recording {} seconds to GIF", self.gif_length.as_secs_f32());
+
+        self.recording = Some(Recording {
+            frames: Vec::new(),
+            started: Instant::now(),
+            length: self.gif_length,
+        });
+    }
+
+    pub fn process(&mut self, pix: &Painter) {
+        if self.screenshot {
+            self.screenshot = false;
+
+            if let Some(path) = capture_path(self.screenshot_format)
+                && pix.to_logical_image().save(&path).is_some()
+            {
+                let path = path.display();
+                info!("Saved screenshot to {}", path);
+            }
+        }
+
+        let Some(rec) = self.recording.as_mut() else {
+            return;
+        };
+
+        let time = rec.started.elapsed();
+
+        if time >= rec.length {
+            self.finish_recording();
+            return;
+        }
+
+        if rec
+            .frames
+            .last()
+            .is_none_or(|&(_, last)| time - last >= GIF_FRAME_INTERVAL)
+        {
+            rec.frames.push((pix.to_logical_image(), time));
+        }
+    }
+
+    /// Encodes on another thread so that rendering does not stall.
+    fn finish_recording(&mut self) {
+        let Some(rec) = self.recording.take() else {
+            return;
+        };
+
+        if rec.frames.is_empty() {
+            return;
+        }
+
+        let Some(path) = capture_path("gif") else {
+            return;
+        };
+
+        // Rounded from the start so that the delays do not drift.
+        let cs = |t: Duration| (t.as_millis() as u64 + 5) / 10;
+        let end = rec.started.elapsed();
+
+        let times: Vec<Duration> = rec.frames.iter().map(|&(_, t)| t).collect();
+
+        let frames: Vec<(Image, u16)> = rec
+            .frames
+            .into_iter()
+            .enumerate()
+            .map(|(i, (image, t))| {
+                let next = times.get(i + 1).copied().unwrap_or(end);
+                (image, (cs(next) - cs(t)).max(1) as u16)
+            })
+            .collect();
+
+        info!("Encoding {} frames to GIF...", frames.len());
+
+        thread::spawn(move || {
+            let bytes = gif::encode(&frames);
+
+            match std::fs::write(&path, bytes) {
+                Ok(()) => {
+                    let path = path.display();
+                    info!("Saved GIF to {}", path)
+                }
+                Err(e) => {
+                    let path = path.display();
+                    error!("Failed to write {}: {}", path, e)
+                }
+            }
+        });
+    }
+}
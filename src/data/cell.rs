@@ -0,0 +1,253 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::audio::AudioBuffer;
+use crate::data::{DEFAULT_TRANSITION_DURATION, KeyInput};
+use crate::graphics::image::Image;
+use crate::graphics::layer::{Layer, LayerStack};
+use crate::graphics::postfx::{PostEffect, PostFx};
+use crate::graphics::transition::Transition;
+use crate::graphics::{P2, Painter};
+use crate::visualizers::{VisList, VisualizerArgs};
+
+/// The visualizer switched away from, kept running
+/// on a canvas of its own until the transition ends.
+struct Outgoing {
+    index: usize,
+    kind: Transition,
+
+    /// From 0 to 1.
+    progress: f32,
+
+    visualizer: Layer,
+    overlay: Layer,
+    frame: Layer,
+    postfx: PostFx,
+    effect_chain: Vec<PostEffect>,
+}
+
+/// One viewport of the layout, running a visualizer of its own
+/// with its own layers, post effects and state.
+pub struct Cell {
+    pub vislist: VisList,
+
+    pub layers: LayerStack,
+
+    /// Background and visualizer composited, so that post
+    /// effects stay inside the cell.
+    frame: Layer,
+    postfx: PostFx,
+    effect_chain: Vec<PostEffect>,
+
+    outgoing: Option<Outgoing>,
+    pub transition: Transition,
+    pub transition_duration: Duration,
+
+    /// Theme the background layer was last drawn with.
+    background_theme: Option<usize>,
+
+    /// Top left corner in physical pixels.
+    origin: P2,
+}
+
+/// Composites `background` and `visualizer` into `frame`
+/// and runs post effects on it.
+fn compose(
+    like: &Painter,
+    background: &Layer,
+    visualizer: &Layer,
+    frame: &mut Layer,
+    postfx: &mut PostFx,
+    effects: &[PostEffect],
+) {
+    let mut frame = frame.painter(like);
+
+    if !visualizer.covers() {
+        frame.composite(background, P2(0, 0));
+    }
+
+    frame.composite(visualizer, P2(0, 0));
+
+    postfx.apply(&mut frame, effects);
+}
+
+impl Cell {
+    pub fn new() -> Self {
+        Self {
+            vislist: VisList::new(),
+            layers: LayerStack::new(),
+            frame: Layer::opaque(),
+            postfx: PostFx::default(),
+            effect_chain: Vec::new(),
+            outgoing: None,
+            transition: Transition::Random,
+            transition_duration: DEFAULT_TRANSITION_DURATION,
+            background_theme: None,
+            origin: P2(0, 0),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frame.width() == 0 || self.frame.height() == 0
+    }
+
+    /// Moves and resizes the cell, in physical pixels.
+    pub fn fit(&mut self, origin: P2, width: usize, height: usize) {
+        self.origin = origin;
+
+        if self.layers.fit(width, height) {
+            self.background_theme = None;
+            self.outgoing = None;
+        }
+
+        self.frame.fit(width, height);
+    }
+
+    /// Redraws the background layer after a resize or theme change.
+    pub fn draw_background(&mut self, like: &Painter, theme: usize, image: Option<Rc<Image>>) {
+        if self.background_theme == Some(theme) {
+            return;
+        }
+
+        // Only redrawn on changes, so it has to cover both fields.
+        let mut bg = self.layers.background.painter_full(like);
+        bg.background_image(image);
+        bg.clear();
+
+        self.background_theme = Some(theme);
+    }
+
+    fn start_transition(&mut self, index: usize) {
+        let kind = self.transition.resolve();
+
+        if kind == Transition::None || self.transition_duration.is_zero() {
+            self.outgoing = None;
+            return;
+        }
+
+        let (w, h) = (self.frame.width(), self.frame.height());
+
+        let mut overlay = Layer::transparent();
+        let mut frame = Layer::opaque();
+        overlay.fit(w, h);
+        frame.fit(w, h);
+
+        // Both carry on from the canvas they shared so far.
+        self.outgoing = Some(Outgoing {
+            index,
+            kind,
+            progress: 0.0,
+            visualizer: self.layers.visualizer.clone(),
+            overlay,
+            frame,
+            postfx: PostFx::default(),
+            effect_chain: Vec::new(),
+        });
+    }
+
+    /// Runs the visualizer on the cell's layers, and the
+    /// outgoing one on its own during a transition.
+    pub fn perform(
+        &mut self,
+        like: &Painter,
+        effects: &[PostEffect],
+        keys: &KeyInput,
+        stream: &mut AudioBuffer,
+        delta: f32,
+    ) {
+        if let Some(index) = self.vislist.take_outgoing() {
+            self.start_transition(index);
+        }
+
+        if let Some(out) = &mut self.outgoing {
+            out.progress += delta / self.transition_duration.as_secs_f32();
+        }
+
+        if self
+            .outgoing
+            .as_ref()
+            .is_some_and(|out| out.progress >= 1.0)
+        {
+            self.outgoing = None;
+        }
+
+        if let Some(out) = &mut self.outgoing {
+            let cursor = stream.cursor();
+            let vis = self.vislist.get_at(out.index);
+
+            out.overlay.clear();
+            out.effect_chain.clear();
+            out.effect_chain
+                .extend(vis.config().effects.iter().chain(effects));
+
+            vis.perform(VisualizerArgs {
+                pix: &mut out.visualizer.painter(like),
+                overlay: &mut out.overlay.painter(like),
+                keys: &KeyInput::default(),
+                stream,
+                delta,
+            });
+
+            stream.set_cursor(cursor);
+        }
+
+        let layers = &mut self.layers;
+        layers.overlay.clear();
+
+        let vis = self.vislist.get();
+
+        self.effect_chain.clear();
+        self.effect_chain
+            .extend(vis.config().effects.iter().chain(effects));
+
+        vis.perform(VisualizerArgs {
+            pix: &mut layers.visualizer.painter(like),
+            overlay: &mut layers.overlay.painter(like),
+            keys,
+            stream,
+            delta,
+        });
+    }
+
+    pub fn overlay<'a>(&'a mut self, like: &Painter) -> Painter<'a> {
+        self.layers.overlay.painter(like)
+    }
+
+    /// Composites the background and visualizer onto `pix`,
+    /// with post effects.
+    pub fn present(&mut self, pix: &mut Painter) {
+        let layers = &self.layers;
+
+        compose(
+            pix,
+            &layers.background,
+            &layers.visualizer,
+            &mut self.frame,
+            &mut self.postfx,
+            &self.effect_chain,
+        );
+
+        let Some(out) = &mut self.outgoing else {
+            pix.composite(&self.frame, self.origin);
+            return;
+        };
+
+        compose(
+            pix,
+            &layers.background,
+            &out.visualizer,
+            &mut out.frame,
+            &mut out.postfx,
+            &out.effect_chain,
+        );
+
+        // Its overlay fades out along with it.
+        out.frame.painter(pix).composite(&out.overlay, P2(0, 0));
+
+        pix.transition(out.kind, &out.frame, &self.frame, out.progress, self.origin);
+    }
+
+    pub fn present_overlay(&self, pix: &mut Painter) {
+        pix.composite(&self.layers.overlay, self.origin);
+    }
+}
@@ -1,27 +1,36 @@
+use std::rc::Rc;
+
 use crate::{
     data::*,
-    modes::{Mode::*, console_mode::Flusher},
+    graphics::{blend::BlendMode, image::Image, postfx::PostEffect, transition::Transition},
+    modes::{Mode::*, console_mode::Flusher, render_mode::FrameFormat},
 };
 
 #[cfg(target_os = "linux")]
 use desktop::create_tmp_desktop_file;
 
 impl Program {
+    /// Parses the options after the program name, or after the
+    /// subcommand, which has set the mode already.
     pub fn eval_args(mut self, args: &mut dyn Iterator<Item = &String>) -> Self {
-        let mut mode = Win;
+        let mut mode = self.mode;
 
         let mut flusher: Flusher = Program::print_ascii;
 
         let mut size = (DEFAULT_SIZE_WIN, DEFAULT_SIZE_WIN);
-        let mut scale = 2;
+        let mut scale = None;
         let mut milli_hz: Option<u32> = None;
-        let mut vis: Option<String> = None;
-        let mut effect = RenderEffect::Interlaced;
+        let mut vis: Vec<String> = Vec::new();
+        let mut layout = (1, 1);
+        let mut transition = Transition::Random;
+        let mut transition_duration = DEFAULT_TRANSITION_DURATION;
+        let mut theme: Option<String> = None;
+        let mut effect: Option<RenderEffect> = None;
+        let mut blend: Option<BlendMode> = None;
         let mut resize = false;
         let mut max_con_size = (50, 50);
 
         let mut args = args.peekable();
-        args.next();
 
         while let Some(arg) = args.next() {
             let arg = arg.as_str();
@@ -34,13 +43,81 @@ impl Program {
 
                 "--quiet" => self.quiet = true,
 
+                "--input" => {
+                    let path = args
+                        .next()
+                        .expect("Argument error: Expected path to a WAV file.");
+
+                    self.render_props.input = Some(path.clone());
+                }
+
+                "--out" => {
+                    let path = args
+                        .next()
+                        .expect("Argument error: Expected output directory or file.");
+
+                    self.render_props.output = Some(path.clone());
+                }
+
+                "--format" => {
+                    let format = args
+                        .next()
+                        .expect("Argument error: Expected one of png, qoi, y4m.");
+
+                    self.render_props.format =
+                        Some(FrameFormat::parse(format).expect("Invalid value for format."));
+                }
+
                 "--ascii" => mode = ConAscii,
 
+                "--half" => (mode, flusher) = (ConHalf, Program::print_half),
+
                 "--braille" => (mode, flusher) = (ConBrail, Program::print_brail),
 
+                "--sextant" => (mode, flusher) = (ConSextant, Program::print_sextant),
+
+                "--octant" => (mode, flusher) = (ConOctant, Program::print_octant),
+
+                "--sixel" => (mode, flusher) = (ConSixel, Program::print_sixel),
+
+                "--kitty" => (mode, flusher) = (ConKitty, Program::print_kitty),
+
                 "--block" => (mode, flusher) = (ConBlock, Program::print_block),
 
-                "--no-auto-switch" => self.vislist.auto_switch = false,
+                "--inline" => {
+                    let rows = args
+                        .next()
+                        .expect("Argument error: Expected number of rows.")
+                        .parse::<u16>()
+                        .expect("Argument error: Invalid value.");
+
+                    if rows == 0 {
+                        panic!("Argument error: Inline mode needs at least one row.");
+                    }
+
+                    self.console_props.inline = Some(rows);
+
+                    if !mode.is_con() {
+                        mode = ConAscii;
+                    }
+                }
+
+                "--hud" => {
+                    let position = args
+                        .next()
+                        .expect("Argument error: Expected one of top, bottom.");
+
+                    self.console_props.hud = true;
+                    self.console_props.hud_top = match position.as_str() {
+                        "top" => true,
+                        "bottom" => false,
+                        _ => panic!("Invalid value for hud."),
+                    };
+                }
+
+                "--stats" => self.console_props.stats = true,
+
+                "--no-auto-switch" => self.auto_switch = false,
 
                 "--size" => {
                     let s = args
@@ -54,19 +131,21 @@ impl Program {
                 }
 
                 "--scale" => {
-                    scale = args
+                    let s = args
                         .next()
                         .expect("Argument error: Expected u8 value for scale")
                         .parse::<u8>()
                         .expect("Argument error: Scale must be a positive integer");
 
-                    if scale > MAX_SCALE_FACTOR {
+                    if s > MAX_SCALE_FACTOR {
                         panic!("Argument error: scale exceeds maximum allowed {MAX_SCALE_FACTOR}.");
                     }
 
-                    if scale == 0 {
+                    if s == 0 {
                         panic!("Argument error: scale needs to be larger than 0.");
                     }
+
+                    scale = Some(s);
                 }
 
                 "--fps" => {
@@ -107,28 +186,150 @@ impl Program {
                 }
 
                 "--vis" => {
-                    let vis_name = args
+                    let vis_names = args
                         .next()
                         .expect("Argument error: Expected name of visualizer");
 
-                    vis = Some(vis_name.clone());
+                    vis = vis_names.split(',').map(|s| s.trim().to_owned()).collect();
+                }
+
+                "--layout" => {
+                    let s = args
+                        .next()
+                        .expect("Argument error: Expected value for layout")
+                        .split('x')
+                        .map(|x| x.parse::<usize>().expect("Argument error: Invalid value"))
+                        .collect::<Vec<_>>();
+
+                    if s.len() != 2 || s[0] == 0 || s[1] == 0 {
+                        panic!("Argument error: layout must look like 2x2.");
+                    }
+
+                    if s[0] * s[1] > MAX_CELLS {
+                        panic!("Argument error: layout exceeds maximum of {MAX_CELLS} cells.");
+                    }
+
+                    layout = (s[0], s[1]);
+                }
+
+                "--background" => {
+                    let path = args
+                        .next()
+                        .expect("Argument error: Expected path to a QOI or PNG image.");
+
+                    self.background = Image::load(path).map(Rc::new);
+                }
+
+                "--blend" => {
+                    let name = args.next().expect(
+                        "Argument error: Expected one of normal, add, screen, multiply, \
+                        overlay, lighten, darken, difference.",
+                    );
+
+                    blend = Some(BlendMode::parse(name).expect("Invalid value for blend."));
+                }
+
+                "--transition" => {
+                    let name = args.next().expect(
+                        "Argument error: Expected one of none, crossfade, wipe, \
+                        slide, dissolve, zoom, random.",
+                    );
+
+                    transition = Transition::parse(name).expect("Invalid value for transition.");
+                }
+
+                "--transition-duration" => {
+                    let secs = args
+                        .next()
+                        .expect("Argument error: Expected duration in seconds.")
+                        .parse::<f32>()
+                        .expect("Argument error: Invalid value.");
+
+                    if secs < 0.0 {
+                        panic!("Argument error: transition duration can not be negative.");
+                    }
+
+                    transition_duration = Duration::from_secs_f32(secs);
+                }
+
+                "--theme" => {
+                    let name = args.next().expect("Argument error: Expected name of theme");
+
+                    theme = Some(name.clone());
+                }
+
+                "--screenshot-format" => {
+                    let format = args.next().expect("Argument error: Expected png or qoi.");
+
+                    self.capture.screenshot_format = match format.as_str() {
+                        "png" => "png",
+                        "qoi" => "qoi",
+                        _ => panic!("Invalid value for screenshot format."),
+                    };
+                }
+
+                "--gif-length" => {
+                    let secs = args
+                        .next()
+                        .expect("Argument error: Expected length of GIF recordings in seconds.")
+                        .parse::<f32>()
+                        .expect("Argument error: Invalid value.");
+
+                    if secs <= 0.0 {
+                        panic!("Argument error: GIF length needs to be larger than 0.");
+                    }
+
+                    self.capture.gif_length = Duration::from_secs_f32(secs);
                 }
 
                 ":3" => {
                     error!("\n:3");
                 }
 
+                "--filter" => {
+                    let name = args.next().expect(
+                        "Argument error: Expected one of nearest, sharp, scanline, scale2x.",
+                    );
+
+                    self.upscale = Some(Upscale::parse(name).expect("Invalid value for filter."));
+                }
+
+                "--colors" => {
+                    let depth = args
+                        .next()
+                        .expect("Argument error: Expected one of mono, 16, 256, truecolor.");
+
+                    let depth = ColorDepth::parse(depth).expect("Invalid value for colors.");
+
+                    // Asked for explicitly, so it beats NO_COLOR.
+                    crossterm::style::force_color_output(depth != ColorDepth::Mono);
+                    self.console_props.colors = depth;
+                }
+
+                "--dither" => {
+                    let name = args
+                        .next()
+                        .expect("Argument error: Expected one of none, ordered, floyd-steinberg.");
+
+                    self.console_props.dither =
+                        Dither::parse(name).expect("Invalid value for dither.");
+                }
+
                 "--effect" => {
                     let val = args
                         .next()
                         .expect("Expecting values of the following: crt, interlaced.");
 
-                    effect = match val.as_str() {
-                        "crt" => RenderEffect::Crt,
-                        "interlaced" => RenderEffect::Interlaced,
-                        "none" => RenderEffect::None,
-                        _ => panic!("Invalid value for effect."),
-                    };
+                    for name in val.split(',') {
+                        match name {
+                            "crt" => effect = Some(RenderEffect::Crt),
+                            "interlaced" => effect = Some(RenderEffect::Interlaced),
+                            "none" => effect = Some(RenderEffect::None),
+                            _ => self
+                                .effects
+                                .push(PostEffect::parse(name).expect("Invalid value for effect.")),
+                        }
+                    }
                 }
 
                 &_ => error!("Argument error: Unknown option {}", arg),
@@ -143,6 +344,13 @@ impl Program {
 
         self.update_size(size);
 
+        if mode.is_pixels() && !mode.is_supported() {
+            alert!(
+                "This terminal does not look like it supports {} graphics, trying anyway.",
+                mode.get_name()
+            );
+        }
+
         if self.quiet || self.mode.is_con() {
             super::log::set_log_enabled(false);
         }
@@ -151,14 +359,56 @@ impl Program {
         self.console_props.flusher = flusher;
         self.console_props.set_max(max_con_size);
         self.update_size(size);
-        self.scale = scale;
         self.resize = resize;
 
-        if let Some(s) = vis {
-            self.vislist.select_by_name(&s);
+        // Rendered frames come out at the given size unless asked otherwise.
+        self.scale = scale.unwrap_or(if mode == Render { 1 } else { DEFAULT_WIN_SCALE });
+
+        if mode == Render {
+            self.auto_switch = false;
         }
 
-        self.win_render_effect = effect;
+        self.layout = layout;
+        self.cells = (0..layout.0 * layout.1)
+            .map(|i| {
+                let mut cell = Cell::new();
+
+                // Cells without a name given start on different visualizers.
+                match vis.get(i) {
+                    Some(name) => cell.vislist.select_by_name(name),
+                    None if i > 0 => cell.vislist.select(i),
+                    None => {}
+                }
+
+                cell.vislist.auto_switch = self.auto_switch;
+                cell.transition = transition;
+                cell.transition_duration = transition_duration;
+
+                // By default the background shows through
+                // the dark parts of the visualizer.
+                cell.layers.visualizer.blend = match (blend, &self.background) {
+                    (Some(b), _) => b,
+                    (None, Some(_)) => BlendMode::Lighten,
+                    (None, None) => BlendMode::Normal,
+                };
+
+                cell
+            })
+            .collect();
+
+        if let Some(s) = theme {
+            self.set_theme(&s);
+        }
+
+        // Upscaling draws every row, there are no fields to interlace.
+        self.win_render_effect = match (effect, self.upscale) {
+            (Some(RenderEffect::Crt | RenderEffect::Interlaced), Some(_)) => {
+                alert!("--filter replaces the crt and interlaced effects.");
+                RenderEffect::None
+            }
+            (_, Some(_)) => RenderEffect::None,
+            (e, None) => e.unwrap_or(RenderEffect::Interlaced),
+        };
 
         if let Some(m) = milli_hz {
             if self.mode == Win && self.wayland() {
@@ -169,6 +419,10 @@ impl Program {
             self.lock_refresh_rate = true;
         }
 
+        if mode == Render {
+            self.delta = Delta::fixed(1000.0 / self.milli_hz as f32);
+        }
+
         self
     }
 
@@ -2,16 +2,30 @@ use std::time::Instant;
 
 pub struct Delta {
     last_call: Instant,
+    fixed: Option<f32>,
 }
 
 impl Delta {
     pub fn new() -> Self {
         Self {
             last_call: Instant::now(),
+            fixed: None,
+        }
+    }
+
+    /// Always ticks by `step` seconds, for rendering offline.
+    pub fn fixed(step: f32) -> Self {
+        Self {
+            fixed: Some(step),
+            ..Self::new()
         }
     }
 
     pub fn tick(&mut self) -> f32 {
+        if let Some(step) = self.fixed {
+            return step;
+        }
+
         let now = Instant::now();
         let elapsed = now.duration_since(self.last_call);
         self.last_call = now;
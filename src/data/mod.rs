@@ -8,12 +8,25 @@ pub mod delta;
 
 pub mod config;
 
+pub mod capture;
+
+pub mod cell;
+
+use std::rc::Rc;
 use std::time::Duration;
 
 use crate::audio::AudioBuffer;
+use crate::data::capture::Capture;
+use crate::data::cell::Cell;
 use crate::data::delta::Delta;
-use crate::graphics::RenderEffect;
-use crate::visualizers::{VisList, VisualizerArgs, VisualizerConfig};
+use crate::graphics::font::{FONT_4X6, FONT_6X8, TextAlign};
+use crate::graphics::image::Image;
+use crate::graphics::palette::{ColorDepth, Dither};
+use crate::graphics::postfx::PostEffect;
+use crate::graphics::theme::{THEMES, Theme};
+use crate::graphics::upscale::Upscale;
+use crate::graphics::{Argb, P2, Pixel, RenderEffect};
+use crate::visualizers::VisualizerConfig;
 use crate::{graphics::Painter, modes::Mode};
 
 use crate::modes;
@@ -43,8 +56,20 @@ pub const MAX_SCALE_FACTOR: u8 = 4;
 
 pub const DEFAULT_BG_COLOR: u32 = u32::from_be_bytes([0, 0x24, 0x24, 0x24]);
 
+pub const BACKGROUND_IMAGE_TINT: u32 = u32::from_be_bytes([0xFF, 0x60, 0x60, 0x60]);
+
 pub const DEFAULT_VIS_SWITCH_DURATION: Duration = Duration::from_secs(8);
 
+/// How long the visualizer name stays on screen after a switch.
+pub const VIS_NAME_DURATION: Duration = Duration::from_millis(1500);
+
+pub const DEFAULT_GIF_LENGTH: Duration = Duration::from_secs(5);
+
+pub const DEFAULT_TRANSITION_DURATION: Duration = Duration::from_millis(500);
+
+/// Most cells a layout can have.
+pub const MAX_CELLS: usize = 16;
+
 #[derive(Default, Debug)]
 pub struct KeyInput {
     pub z: bool,
@@ -70,6 +95,10 @@ pub(crate) struct Program {
 
     win_render_effect: crate::graphics::RenderEffect,
 
+    /// Draw at the logical size and blow the frame up with this,
+    /// instead of drawing at the physical size.
+    upscale: Option<Upscale>,
+
     pub key: KeyInput,
 
     mode: Mode,
@@ -82,10 +111,32 @@ pub(crate) struct Program {
 
     pub console_props: modes::console_mode::ConsoleProps,
 
-    vislist: crate::visualizers::VisList,
+    pub render_props: modes::render_mode::RenderProps,
+
+    /// Row by row, `layout.0` columns and `layout.1` rows.
+    cells: Vec<Cell>,
+    layout: (usize, usize),
+
+    /// The cell that keys go to.
+    focus: usize,
+
+    background: Option<Rc<Image>>,
+
+    theme: usize,
+
+    effects: Vec<PostEffect>,
+
+    capture: Capture,
 
     auto_switch: bool,
 
+    /// Overlays on the focused cell.
+    show_fps: bool,
+    show_hints: bool,
+
+    /// Frames per second, smoothed over the last few frames.
+    fps: f32,
+
     delta: Delta,
 }
 
@@ -106,6 +157,7 @@ impl Program {
             wayland: true,
 
             win_render_effect: RenderEffect::Interlaced,
+            upscale: None,
 
             mode: default_mode,
 
@@ -115,10 +167,24 @@ impl Program {
             refresh_rate_interval: rate,
             lock_refresh_rate: false,
 
-            vislist: VisList::new(),
+            cells: vec![Cell::new()],
+            layout: (1, 1),
+            focus: 0,
+
+            background: None,
+
+            theme: 0,
+
+            effects: Vec::new(),
+
+            capture: Capture::new(),
 
             auto_switch: true,
 
+            show_fps: false,
+            show_hints: false,
+            fps: 0.0,
+
             delta: Delta::new(),
 
             window_props: modes::windowed_mode::WindowProps {
@@ -135,8 +201,24 @@ impl Program {
 
                 max_width: 50,
                 max_height: 25,
+
+                cell_width: 8,
+                cell_height: 16,
+
+                colors: ColorDepth::detect(),
+                dither: Dither::None,
+
+                stats: false,
+                inline: None,
+                hud: false,
+                hud_top: false,
+                top: 0,
+                cleared: false,
+
                 flusher: default_mode.get_flusher(),
             },
+
+            render_props: modes::render_mode::RenderProps::default(),
         }
     }
 
@@ -148,6 +230,19 @@ impl Program {
         self.nosleep
     }
 
+    pub fn auto_switch(&self) -> bool {
+        self.auto_switch
+    }
+
+    /// Name of the visualizer in the focused cell.
+    pub fn vis_name(&self) -> &'static str {
+        self.cells[self.focus].vislist.name()
+    }
+
+    pub fn milli_hz(&self) -> u32 {
+        self.milli_hz
+    }
+
     pub fn get_rr_interval(&self) -> Duration {
         self.refresh_rate_interval
     }
@@ -156,6 +251,10 @@ impl Program {
         self.win_render_effect
     }
 
+    pub fn get_upscale(&self) -> Option<Upscale> {
+        self.upscale
+    }
+
     pub fn is_resizable(&self) -> bool {
         self.resize
     }
@@ -188,48 +287,286 @@ impl Program {
     }
 
     pub fn change_visualizer(&mut self, forward: bool) {
+        let vislist = &mut self.cells[self.focus].vislist;
+
         if forward {
-            self.vislist.next();
+            vislist.next();
         } else {
-            self.vislist.prev();
+            vislist.prev();
         }
 
-        let conf = self.vislist.get().config();
+        let conf = vislist.get().config();
         self.apply_vis_config(conf);
     }
 
     pub fn autoupdate_visualizer(&mut self) {
-        if let Some(conf) = self.vislist.update() {
-            self.apply_vis_config(conf);
+        for (i, cell) in self.cells.iter_mut().enumerate() {
+            let conf = cell.vislist.update();
+
+            if i == self.focus
+                && let Some(conf) = conf
+            {
+                self.nosleep = conf.nosleep;
+                crate::audio::get_buf().set_normalize(conf.normalize);
+            }
         }
     }
 
+    /// Moves keys to the next cell of the layout.
+    pub fn cycle_focus(&mut self) {
+        self.focus = (self.focus + 1) % self.cells.len();
+
+        let vislist = &mut self.cells[self.focus].vislist;
+        vislist.show_name();
+
+        let conf = vislist.get().config();
+        self.apply_vis_config(conf);
+    }
+
     pub fn update_size(&mut self, s: (u16, u16)) {
         match &self.mode {
-            Mode::Win => self.window_props.set_size(s),
+            Mode::Win | Mode::Render => self.window_props.set_size(s),
             _ => self.console_props.set_size(s, self.mode()),
         }
     }
 
-    pub fn render<'a>(&'a mut self, pix: &'a mut Painter<'a>, buf: &'a mut AudioBuffer) {
-        self.vislist.get().perform(VisualizerArgs {
-            pix,
-            keys: &self.key,
-            stream: buf,
-            delta: self.delta.tick(),
-        });
+    pub fn render(&mut self, pix: &mut Painter, buf: &mut AudioBuffer) {
+        pix.set_theme(&THEMES[self.theme]);
+
+        self.fit_cells(pix);
+
+        let delta = self.delta.tick();
+        self.fps = self.fps * 0.9 + 0.1 / delta.max(1e-3);
+
+        let no_keys = KeyInput::default();
+        let show_focus = self.cells.len() > 1;
+
+        // Every cell reads the same samples, the focused
+        // one decides where reading continues from.
+        let cursor = buf.cursor();
+        let mut next_cursor = cursor;
+
+        for (i, cell) in self.cells.iter_mut().enumerate() {
+            if cell.is_empty() {
+                continue;
+            }
+
+            let keys = if i == self.focus { &self.key } else { &no_keys };
+
+            buf.set_cursor(cursor);
+            cell.draw_background(pix, self.theme, self.background.clone());
+            cell.perform(pix, &self.effects, keys, buf, delta);
+
+            if i == self.focus {
+                next_cursor = buf.cursor();
+            }
+
+            // The overlay follows the wall clock, which would
+            // make offline renders differ between runs.
+            if self.mode != Mode::Render {
+                let name = cell.vislist.recent_name();
+                let mut overlay = cell.overlay(pix);
+
+                if let Some(name) = name {
+                    Self::draw_vis_name(&mut overlay, name);
+
+                    if i == self.focus && show_focus {
+                        Self::draw_focus(&mut overlay);
+                    }
+                }
+
+                if i == self.focus && self.show_fps {
+                    Self::draw_fps(&mut overlay, self.fps);
+                }
+
+                if i == self.focus && self.show_hints {
+                    Self::draw_hints(&mut overlay);
+                }
+            }
+
+            cell.present(pix);
+        }
+
+        buf.set_cursor(next_cursor);
+
+        self.capture.process(pix);
+
+        for cell in &self.cells {
+            cell.present_overlay(pix);
+        }
+    }
+
+    /// Splits the canvas along logical pixels, so that
+    /// every cell keeps the scale of the canvas.
+    fn fit_cells(&mut self, pix: &Painter) {
+        let (w, h) = pix.logical_sizeu();
+        let (cols, rows) = self.layout;
+        let scale = pix.scale_factor();
+
+        for (i, cell) in self.cells.iter_mut().enumerate() {
+            let (col, row) = (i % cols, i / cols);
+
+            let x0 = col * w / cols;
+            let x1 = (col + 1) * w / cols;
+            let y0 = row * h / rows;
+            let y1 = (row + 1) * h / rows;
+
+            cell.fit(
+                P2((x0 * scale) as i32, (y0 * scale) as i32),
+                (x1 - x0) * scale,
+                (y1 - y0) * scale,
+            );
+        }
+    }
+
+    /// A frame around the focused cell.
+    fn draw_focus(pix: &mut Painter) {
+        let P2(w, h) = pix.logical_size();
+        let color = pix.theme().foreground(Argb::white());
+
+        pix.mixerd();
+        pix.color(color);
+        pix.rect(P2(0, 0), w as usize, 1);
+        pix.rect(P2(0, h - 1), w as usize, 1);
+        pix.rect(P2(0, 0), 1, h as usize);
+        pix.rect(P2(w - 1, 0), 1, h as usize);
+    }
+
+    fn draw_vis_name(pix: &mut Painter, name: &str) {
+        let font = FONT_4X6;
+        let size = font.measure(name);
+        let P2(w, _) = pix.logical_size();
+
+        // Skip when the name does not fit, tiny console sizes
+        // are better off without it.
+        if size.0 + 2 > w {
+            return;
+        }
+
+        let pos = P2(w / 2, 2);
+
+        pix.mixerd();
+        let theme = pix.theme();
+
+        pix.color(theme.background);
+        pix.rect(
+            P2(pos.0 - size.0 / 2 - 1, pos.1 - 1),
+            size.0 as usize + 2,
+            size.1 as usize + 2,
+        );
+
+        pix.color(theme.foreground(Argb::white()));
+        pix.font(font);
+        pix.text_align(TextAlign::Center);
+        pix.text(pos, name);
+    }
+
+    /// Frame rate in the top right corner.
+    fn draw_fps(pix: &mut Painter, fps: f32) {
+        let font = FONT_4X6;
+        let text = format!("{:.0}", fps);
+        let size = font.measure(&text);
+        let P2(w, _) = pix.logical_size();
+
+        if size.0 + 2 > w {
+            return;
+        }
+
+        let pos = P2(w - 2, 2);
+
+        pix.mixerd();
+        let theme = pix.theme();
+
+        pix.color(theme.background);
+        pix.rect(
+            P2(pos.0 - size.0 - 1, pos.1 - 1),
+            size.0 as usize + 2,
+            size.1 as usize + 2,
+        );
+
+        pix.color(theme.foreground(Argb::white()));
+        pix.font(font);
+        pix.text_align(TextAlign::Right);
+        pix.text(pos, &text);
+    }
+
+    /// Most used keys in the bottom left corner.
+    fn draw_hints(pix: &mut Painter) {
+        const HINTS: &str = "n/b vis\nt theme\np shot\ng gif\nf fps\nh keys\nq quit";
+
+        let font = FONT_6X8;
+        let size = font.measure(HINTS);
+        let P2(w, h) = pix.logical_size();
+
+        if size.0 + 4 > w || size.1 + 4 > h {
+            return;
+        }
+
+        let pos = P2(2, h - size.1 - 2);
+
+        pix.mixerd();
+        let theme = pix.theme();
+
+        pix.color(theme.background);
+        pix.rect(
+            P2(pos.0 - 1, pos.1 - 1),
+            size.0 as usize + 2,
+            size.1 as usize + 2,
+        );
+
+        pix.color(theme.foreground(Argb::white()));
+        pix.font(font);
+        pix.text_align(TextAlign::Left);
+        pix.text(pos, HINTS);
+    }
+
+    pub fn set_theme(&mut self, name: &str) {
+        if let Some(i) = Theme::find(name) {
+            self.theme = i;
+            return;
+        }
+
+        error!("Invalid theme specified: \"{}\"", name);
+        info!("Possible theme values include: ");
+
+        for t in THEMES {
+            info!("    {}", t.name);
+        }
+
+        info!("");
+    }
+
+    pub fn cycle_theme(&mut self) {
+        self.theme = (self.theme + 1) % THEMES.len();
+        info!("Theme is now {}", THEMES[self.theme].name);
+    }
+
+    pub fn screenshot(&mut self) {
+        self.capture.request_screenshot();
+    }
+
+    pub fn toggle_recording(&mut self) {
+        self.capture.toggle_recording();
+    }
+
+    pub fn toggle_fps(&mut self) {
+        self.show_fps ^= true;
+    }
+
+    pub fn toggle_hints(&mut self) {
+        self.show_hints ^= true;
     }
 
     pub fn toggle_auto_switch(&mut self) {
-        self.vislist.auto_switch ^= true;
+        self.auto_switch ^= true;
+
+        for cell in &mut self.cells {
+            cell.vislist.auto_switch = self.auto_switch;
+        }
 
         info!(
             "Auto switch is now {}",
-            if self.vislist.auto_switch {
-                "on"
-            } else {
-                "off"
-            }
+            if self.auto_switch { "on" } else { "off" }
         );
     }
 
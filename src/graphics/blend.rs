@@ -13,8 +13,9 @@ pub fn u8_mul(a: u8, b: u8) -> u8 {
 }
 
 #[cfg(not(feature = "fast"))]
-// Coffeevis no longer supports true compositing
-// in order to achieve more performance.
+// The default mixers don't do true compositing
+// in order to achieve more performance, see
+// `BlendMode` below for that.
 // This color mixing blends the BG folor to FG color
 // based on FG's alpha value.
 // This explains the interpolation in the name.
@@ -48,10 +49,6 @@ pub fn argb32_interpolate(c1: Argb, c2: Argb) -> Argb {
 }
 
 impl Pixel for Argb {
-    fn black() -> Argb {
-        0x00_00_00_00
-    }
-
     fn white() -> Argb {
         0xFF_FF_FF_FF
     }
@@ -99,3 +96,265 @@ impl Pixel for Argb {
         ])
     }
 }
+
+/// Source over in straight alpha, keeping the alpha of the result.
+/// Used for `mixerm` on transparent layers, where the usual
+/// mixers would lose the alpha.
+pub fn over_straight(dst: Argb, src: Argb) -> Argb {
+    let [sa, sr, sg, sb] = src.decompose();
+    let [da, dr, dg, db] = dst.decompose();
+
+    if sa == 255 || da == 0 {
+        return src;
+    }
+
+    if sa == 0 {
+        return dst;
+    }
+
+    let sa = sa as u32;
+    let da = div255(da as u32 * (255 - sa));
+    let a = sa + da;
+
+    let channel = |s: u8, d: u8| ((s as u32 * sa + d as u32 * da + a / 2) / a) as u8;
+
+    Argb::compose([a as u8, channel(sr, dr), channel(sg, dg), channel(sb, db)])
+}
+
+/// Divides by 255 with rounding, exact for every product of two u8s.
+#[inline(always)]
+#[allow(unreachable_code)]
+fn div255(x: u32) -> u32 {
+    #[cfg(feature = "fast")]
+    return x >> 8;
+
+    let x = x + 128;
+    (x + (x >> 8)) >> 8
+}
+
+/// Blend modes with real source alpha.
+///
+/// The canvas is presented opaque and the mixers above do not keep its
+/// alpha, so the destination is always taken as opaque. The source is
+/// premultiplied by its alpha, combined with the destination following
+/// the W3C compositing formulas, and the result is written opaque:
+///
+/// `co = cb * (1 - as) + as * B(cb, cs)`
+///
+/// Colors drawn with these need a proper alpha, 0 draws nothing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlendMode {
+    Normal,
+    Add,
+    Screen,
+    Multiply,
+    Overlay,
+    Lighten,
+    Darken,
+    Difference,
+}
+
+/// Applies a separable blend. `f` gets the destination channel, the
+/// premultiplied source channel and the source alpha, and returns
+/// `as * B(cb, cs)`, all in 0..=255.
+#[inline(always)]
+fn separable(dst: Argb, src: Argb, f: impl Fn(u32, u32, u32) -> u32) -> Argb {
+    let [a, sr, sg, sb] = src.decompose();
+    let [_, dr, dg, db] = dst.decompose();
+
+    // Exactly the destination, which the rounding below would not give.
+    if a == 0 {
+        return dst.set_alpha(0xFF);
+    }
+
+    let a = a as u32;
+
+    let channel = |cb: u8, cs: u8| {
+        let cb = cb as u32;
+        let cs = div255(cs as u32 * a);
+
+        (div255(cb * (255 - a)) + f(cb, cs, a)).min(255) as u8
+    };
+
+    Argb::compose([0xFF, channel(dr, sr), channel(dg, sg), channel(db, sb)])
+}
+
+impl BlendMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "normal" => Self::Normal,
+            "add" => Self::Add,
+            "screen" => Self::Screen,
+            "multiply" => Self::Multiply,
+            "overlay" => Self::Overlay,
+            "lighten" => Self::Lighten,
+            "darken" => Self::Darken,
+            "difference" => Self::Difference,
+            _ => return None,
+        })
+    }
+
+    pub fn mixer(self) -> Mixer {
+        match self {
+            Self::Normal => blend_normal,
+            Self::Add => blend_add,
+            Self::Screen => blend_screen,
+            Self::Multiply => blend_multiply,
+            Self::Overlay => blend_overlay,
+            Self::Lighten => blend_lighten,
+            Self::Darken => blend_darken,
+            Self::Difference => blend_difference,
+        }
+    }
+}
+
+pub fn blend_normal(dst: Argb, src: Argb) -> Argb {
+    separable(dst, src, |_, cs, _| cs)
+}
+
+pub fn blend_add(dst: Argb, src: Argb) -> Argb {
+    separable(dst, src, |cb, cs, a| cs + div255(cb * a))
+}
+
+#[allow(unreachable_code)]
+pub fn blend_screen(dst: Argb, src: Argb) -> Argb {
+    // Close enough to additive when precision is not a concern.
+    #[cfg(feature = "fast")]
+    return blend_add(dst, src);
+
+    separable(dst, src, |cb, cs, a| {
+        (div255(cb * a) + cs).saturating_sub(div255(cb * cs))
+    })
+}
+
+pub fn blend_multiply(dst: Argb, src: Argb) -> Argb {
+    separable(dst, src, |cb, cs, _| div255(cb * cs))
+}
+
+pub fn blend_overlay(dst: Argb, src: Argb) -> Argb {
+    separable(dst, src, |cb, cs, a| {
+        if cb < 128 {
+            2 * div255(cb * cs)
+        } else {
+            a.saturating_sub(2 * div255((255 - cb) * (a - cs)))
+        }
+    })
+}
+
+pub fn blend_lighten(dst: Argb, src: Argb) -> Argb {
+    separable(dst, src, |cb, cs, a| div255(cb * a).max(cs))
+}
+
+pub fn blend_darken(dst: Argb, src: Argb) -> Argb {
+    separable(dst, src, |cb, cs, a| div255(cb * a).min(cs))
+}
+
+pub fn blend_difference(dst: Argb, src: Argb) -> Argb {
+    separable(dst, src, |cb, cs, a| div255(cb * a).abs_diff(cs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODES: [BlendMode; 8] = [
+        BlendMode::Normal,
+        BlendMode::Add,
+        BlendMode::Screen,
+        BlendMode::Multiply,
+        BlendMode::Overlay,
+        BlendMode::Lighten,
+        BlendMode::Darken,
+        BlendMode::Difference,
+    ];
+
+    const LEVELS: [u8; 9] = [0, 1, 64, 127, 128, 129, 200, 254, 255];
+
+    /// `B(cb, cs)` from the W3C compositing spec, in 0..=1.
+    fn reference(mode: BlendMode, cb: f64, cs: f64) -> f64 {
+        match mode {
+            BlendMode::Normal => cs,
+            BlendMode::Add => cb + cs,
+            BlendMode::Screen => cb + cs - cb * cs,
+            BlendMode::Multiply => cb * cs,
+            BlendMode::Overlay if cb <= 0.5 => 2.0 * cb * cs,
+            BlendMode::Overlay => 1.0 - 2.0 * (1.0 - cb) * (1.0 - cs),
+            BlendMode::Lighten => cb.max(cs),
+            BlendMode::Darken => cb.min(cs),
+            BlendMode::Difference => (cb - cs).abs(),
+        }
+    }
+
+    /// Largest difference from `co = cb * (1 - as) + as * B(cb, cs)`
+    /// over every pair of levels, on all three channels.
+    fn max_error(mode: BlendMode, formula: BlendMode, alpha: u8) -> u8 {
+        let mixer = mode.mixer();
+        let mut worst = 0;
+
+        for cb in LEVELS {
+            for cs in LEVELS {
+                let dst = Argb::compose([0xFF, cb, 255 - cb, cb / 2]);
+                let src = Argb::compose([alpha, cs, 255 - cs, 255 - cs / 2]);
+
+                let [ao, ro, go, bo] = mixer(dst, src).decompose();
+                assert_eq!(ao, 0xFF, "{mode:?} is not opaque");
+
+                let a = alpha as f64 / 255.0;
+                let pairs = [(cb, cs), (255 - cb, 255 - cs), (cb / 2, 255 - cs / 2)];
+
+                for ((b, s), o) in pairs.into_iter().zip([ro, go, bo]) {
+                    let (b, s) = (b as f64 / 255.0, s as f64 / 255.0);
+                    let co = b * (1.0 - a) + a * reference(formula, b, s);
+                    let expected = (co.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+                    worst = worst.max(o.abs_diff(expected));
+                }
+            }
+        }
+
+        worst
+    }
+
+    #[test]
+    fn transparent_source_keeps_destination() {
+        for mode in MODES {
+            for cb in LEVELS {
+                for cs in LEVELS {
+                    let dst = Argb::compose([0xFF, cb, 255 - cb, cb / 2]);
+                    let src = Argb::compose([0, cs, 255 - cs, cs / 2]);
+
+                    assert_eq!(mode.mixer()(dst, src), dst, "{mode:?}");
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "fast"))]
+    #[test]
+    fn modes_match_w3c() {
+        for mode in MODES {
+            for alpha in [0, 128, 255] {
+                let err = max_error(mode, mode, alpha);
+                assert!(err <= 1, "{mode:?} at alpha {alpha} is off by {err}");
+            }
+        }
+    }
+
+    /// Rounding is cut short and screen turns into add, which is
+    /// only ever a little brighter.
+    #[cfg(feature = "fast")]
+    #[test]
+    fn fast_modes_stay_close_to_w3c() {
+        for mode in MODES {
+            let formula = match mode {
+                BlendMode::Screen => BlendMode::Add,
+                m => m,
+            };
+
+            for alpha in [0, 128, 255] {
+                let err = max_error(mode, formula, alpha);
+                assert!(err <= 3, "{mode:?} at alpha {alpha} is off by {err}");
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
-use crate::graphics::Painter;
+use crate::graphics::{Painter, blend::u8_mul};
 
 #[allow(unused_imports)]
-use super::{P2, Pixel};
+use super::{Argb, P2, Pixel};
 
 pub fn get_idx_fast(cwidth: usize, p: P2) -> usize {
     let x = p.0.cast_unsigned();
@@ -19,31 +19,87 @@ impl<'a> Painter<'a> {
         if self.fill { self.scale } else { 1 }
     }
 
-    fn __plot_i(&mut self, i: usize) {
+    /// Color to paint at logical point `p`.
+    fn ink(&self, p: P2) -> Argb {
+        match &self.gradient {
+            Some(g) => g.sample(p),
+            None => self.color,
+        }
+    }
+
+    fn __plot_i(&mut self, i: usize, c: Argb) {
         if let Some(p) = self.buffer.get_mut(i) {
             #[cfg(feature = "fast")]
             {
-                *p = self.color;
+                *p = c;
             }
 
             #[cfg(not(feature = "fast"))]
             {
-                *p = (self.mixer)(*p, self.color);
+                *p = (self.mixer)(*p, c);
             }
         }
     }
 
-    fn __plot(&mut self, p: P2) {
+    fn __plot(&mut self, p: P2, c: Argb) {
         let i = get_idx_fast(self.width, p);
-        self.__plot_i(i);
+        self.__plot_i(i, c);
     }
 
     pub fn plot(&mut self, p: P2) {
+        let c = self.ink(p);
+        self.plot_color(p, c);
+    }
+
+    /// Plots with the current color, its alpha
+    /// multiplied by `a`. Used for anti-aliasing.
+    pub fn plot_alpha(&mut self, p: P2, a: u8) {
+        let c = self.ink(p);
+        let [ca, ..] = c.decompose();
+        self.plot_color(p, c.set_alpha(u8_mul(ca, a)));
+    }
+
+    pub fn plot_color(&mut self, p: P2, c: Argb) {
         let p = p.scale(self.scale).field(self.field);
 
         for y in 0..self.row_height() as i32 {
             for x in 0..self.scale as i32 {
-                self.__plot(P2(p.0 + x, p.1 + y));
+                self.__plot(P2(p.0 + x, p.1 + y), c);
+            }
+        }
+    }
+
+    /// Copies the logical pixels of the current field into `out`,
+    /// row by row.
+    pub fn read_logical(&self, out: &mut Vec<Argb>) {
+        let (width, height) = self.logical_sizeu();
+        let scale = self.scale as usize;
+
+        out.clear();
+
+        for y in 0..height {
+            let row = (y * scale + self.field as usize) * self.width;
+            out.extend((0..width).map(|x| self.buffer[row + x * scale]));
+        }
+    }
+
+    /// Writes a full logical frame to every physical row, not just
+    /// the current field, replacing what is there without mixing.
+    pub fn write_logical(&mut self, data: &[Argb]) {
+        let (width, _) = self.logical_sizeu();
+        let scale = self.scale as usize;
+
+        for (y, line) in data.chunks_exact(width.max(1)).enumerate() {
+            for sy in 0..scale {
+                let start = (y * scale + sy) * self.width;
+
+                let Some(row) = self.buffer.get_mut(start..start + width * scale) else {
+                    return;
+                };
+
+                for (chunk, &c) in row.chunks_exact_mut(scale).zip(line) {
+                    chunk.fill(c);
+                }
             }
         }
     }
@@ -62,17 +118,31 @@ impl<'a> Painter<'a> {
         let row_height = self.row_height() as usize;
         let step = self.step() as usize;
 
+        let scale = self.scale as usize;
+
         let iter = self
             .buffer
             .chunks_exact_mut(self.width)
+            .enumerate()
             .skip(ys)
             .take(ye.saturating_sub(ys).wrapping_add(row_height))
             .step_by(step)
-            .flat_map(|l| l.get_mut(xs..xe));
+            .flat_map(|(y, l)| l.get_mut(xs..xe).map(|l| (y, l)));
+
+        match &self.gradient {
+            None => iter.flat_map(|(_, l)| l).for_each(|p| {
+                *p = (self.mixer)(*p, self.color);
+            }),
 
-        iter.flatten().for_each(|p| {
-            *p = (self.mixer)(*p, self.color);
-        });
+            Some(g) => iter.for_each(|(y, l)| {
+                let ly = (y / scale) as i32;
+
+                for (x, p) in l.iter_mut().enumerate() {
+                    let lx = ((xs + x) / scale) as i32;
+                    *p = (self.mixer)(*p, g.sample(P2(lx, ly)));
+                }
+            }),
+        }
     }
 
     pub fn rect(&mut self, ps: P2, w: usize, h: usize) {
@@ -94,44 +164,18 @@ impl<'a> Painter<'a> {
     }
 
     pub fn fill(&mut self) {
+        let scale = self.scale as usize;
+
         for y in (self.field as usize..self.height).step_by(self.step() as usize) {
             let start = y * self.width;
-            self.buffer[start..start+self.width].fill(self.color);
-        }
-    }
+            let row = &mut self.buffer[start..start + self.width];
 
-    pub fn line(&mut self, ps: P2, pe: P2) {
-        let dx = (pe.0 - ps.0).abs();
-        let sx = if ps.0 < pe.0 { 1 } else { -1 };
-        let dy = -(pe.1 - ps.1).abs();
-        let sy = if ps.1 < pe.1 { 1 } else { -1 };
-        let mut error = dx + dy;
+            match &self.gradient {
+                None => row.fill(self.color),
 
-        let mut p = ps;
-
-        loop {
-            self.plot(p);
-
-            if p.0 == pe.0 && p.1 == pe.1 {
-                return;
-            }
-
-            let e2 = error * 2;
-
-            if e2 >= dy {
-                if p.0 == pe.0 {
-                    return;
-                }
-                error += dy;
-                p.0 += sx;
-            }
-
-            if e2 <= dx {
-                if p.1 == pe.1 {
-                    return;
-                }
-                error += dx;
-                p.1 += sy;
+                Some(g) => row.iter_mut().enumerate().for_each(|(x, p)| {
+                    *p = g.sample(P2((x / scale) as i32, (y / scale) as i32));
+                }),
             }
         }
     }
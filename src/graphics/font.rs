@@ -0,0 +1,345 @@
+use crate::graphics::{P2, Painter};
+
+const FIRST_GLYPH: u8 = b' ';
+const LAST_GLYPH: u8 = b'~';
+const GLYPH_COUNT: usize = (LAST_GLYPH - FIRST_GLYPH + 1) as usize;
+
+/// Glyph drawn for characters outside of printable ASCII.
+const FALLBACK: u8 = b'?';
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+/// A fixed width bitmap font covering printable ASCII.
+///
+/// Each glyph is `height` rows of bits, the most significant
+/// of the lowest `width` bits being the leftmost pixel.
+#[derive(Debug, Clone, Copy)]
+pub struct Font {
+    pub width: u8,
+    pub height: u8,
+
+    /// Horizontal distance between the start of two glyphs.
+    pub advance: u8,
+
+    /// Vertical distance between two lines of text.
+    pub line_height: u8,
+
+    glyphs: &'static [u8],
+}
+
+/// 3x5 glyphs in a 4x6 cell.
+pub const FONT_4X6: Font = Font {
+    width: 3,
+    height: 5,
+    advance: 4,
+    line_height: 6,
+    glyphs: &GLYPHS_4X6,
+};
+
+/// 5x7 glyphs in a 6x8 cell.
+pub const FONT_6X8: Font = Font {
+    width: 5,
+    height: 7,
+    advance: 6,
+    line_height: 8,
+    glyphs: &GLYPHS_6X8,
+};
+
+impl Default for Font {
+    fn default() -> Self {
+        FONT_4X6
+    }
+}
+
+impl Font {
+    fn glyph(&self, c: char) -> &'static [u8] {
+        let b = match u8::try_from(c) {
+            Ok(b @ FIRST_GLYPH..=LAST_GLYPH) => b,
+            _ => FALLBACK,
+        };
+
+        let h = self.height as usize;
+        let i = (b - FIRST_GLYPH) as usize * h;
+
+        &self.glyphs[i..i + h]
+    }
+
+    /// Width of a single line of text, without the
+    /// spacing after the last glyph.
+    pub fn line_width(&self, line: &str) -> i32 {
+        match line.chars().count() as i32 {
+            0 => 0,
+            n => n * self.advance as i32 - (self.advance - self.width) as i32,
+        }
+    }
+
+    /// Size of the box covered by `text`.
+    pub fn measure(&self, text: &str) -> P2 {
+        let width = text.lines().map(|l| self.line_width(l)).max().unwrap_or(0);
+        let lines = text.lines().count() as i32;
+
+        match lines {
+            0 => P2(0, 0),
+            n => P2(
+                width,
+                n * self.line_height as i32 - (self.line_height - self.height) as i32,
+            ),
+        }
+    }
+}
+
+impl<'a> Painter<'a> {
+    pub fn font(&mut self, f: Font) {
+        self.font = f;
+    }
+
+    pub fn text_align(&mut self, a: TextAlign) {
+        self.text_align = a;
+    }
+
+    /// Draws `text` with the current font and color. `pos` is the
+    /// top of the first line, on its left edge, center or right
+    /// edge depending on the alignment. Lines are split at `\n`.
+    pub fn text(&mut self, pos: P2, text: &str) {
+        let font = self.font;
+
+        for (row, line) in text.lines().enumerate() {
+            let width = font.line_width(line);
+
+            let x0 = match self.text_align {
+                TextAlign::Left => pos.0,
+                TextAlign::Center => pos.0 - width / 2,
+                TextAlign::Right => pos.0 - width,
+            };
+
+            let y0 = pos.1 + row as i32 * font.line_height as i32;
+
+            for (i, c) in line.chars().enumerate() {
+                let gx = x0 + i as i32 * font.advance as i32;
+                self.glyph(P2(gx, y0), font.glyph(c), font.width);
+            }
+        }
+    }
+
+    fn glyph(&mut self, pos: P2, rows: &[u8], width: u8) {
+        let P2(w, h) = self.logical_size();
+
+        for (y, &bits) in rows.iter().enumerate() {
+            for x in 0..width {
+                if bits >> (width - 1 - x) & 1 == 0 {
+                    continue;
+                }
+
+                let p = P2(pos.0 + x as i32, pos.1 + y as i32);
+
+                // `plot` does not clip horizontally.
+                if (0..w).contains(&p.0) && (0..h).contains(&p.1) {
+                    self.plot(p);
+                }
+            }
+        }
+    }
+}
+
+#[rustfmt::skip]
+const GLYPHS_4X6: [u8; GLYPH_COUNT * 5] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, // ' '
+    0x02, 0x02, 0x02, 0x00, 0x02, // '!'
+    0x05, 0x05, 0x00, 0x00, 0x00, // '"'
+    0x05, 0x07, 0x05, 0x07, 0x05, // '#'
+    0x03, 0x06, 0x02, 0x03, 0x06, // '$'
+    0x05, 0x01, 0x02, 0x04, 0x05, // '%'
+    0x06, 0x06, 0x07, 0x05, 0x03, // '&'
+    0x02, 0x02, 0x00, 0x00, 0x00, // '\''
+    0x01, 0x02, 0x02, 0x02, 0x01, // '('
+    0x04, 0x02, 0x02, 0x02, 0x04, // ')'
+    0x05, 0x02, 0x05, 0x00, 0x00, // '*'
+    0x00, 0x02, 0x07, 0x02, 0x00, // '+'
+    0x00, 0x00, 0x00, 0x02, 0x04, // ','
+    0x00, 0x00, 0x07, 0x00, 0x00, // '-'
+    0x00, 0x00, 0x00, 0x00, 0x02, // '.'
+    0x01, 0x01, 0x02, 0x04, 0x04, // '/'
+    0x03, 0x05, 0x05, 0x05, 0x06, // '0'
+    0x02, 0x06, 0x02, 0x02, 0x02, // '1'
+    0x06, 0x01, 0x02, 0x04, 0x07, // '2'
+    0x06, 0x01, 0x02, 0x01, 0x06, // '3'
+    0x05, 0x05, 0x07, 0x01, 0x01, // '4'
+    0x07, 0x04, 0x06, 0x01, 0x06, // '5'
+    0x03, 0x04, 0x07, 0x05, 0x07, // '6'
+    0x07, 0x01, 0x02, 0x04, 0x04, // '7'
+    0x07, 0x05, 0x07, 0x05, 0x07, // '8'
+    0x07, 0x05, 0x07, 0x01, 0x06, // '9'
+    0x00, 0x02, 0x00, 0x02, 0x00, // ':'
+    0x00, 0x02, 0x00, 0x02, 0x04, // ';'
+    0x01, 0x02, 0x04, 0x02, 0x01, // '<'
+    0x00, 0x07, 0x00, 0x07, 0x00, // '='
+    0x04, 0x02, 0x01, 0x02, 0x04, // '>'
+    0x07, 0x01, 0x02, 0x00, 0x02, // '?'
+    0x02, 0x05, 0x07, 0x04, 0x03, // '@'
+    0x02, 0x05, 0x07, 0x05, 0x05, // 'A'
+    0x06, 0x05, 0x06, 0x05, 0x06, // 'B'
+    0x03, 0x04, 0x04, 0x04, 0x03, // 'C'
+    0x06, 0x05, 0x05, 0x05, 0x06, // 'D'
+    0x07, 0x04, 0x07, 0x04, 0x07, // 'E'
+    0x07, 0x04, 0x07, 0x04, 0x04, // 'F'
+    0x03, 0x04, 0x07, 0x05, 0x03, // 'G'
+    0x05, 0x05, 0x07, 0x05, 0x05, // 'H'
+    0x07, 0x02, 0x02, 0x02, 0x07, // 'I'
+    0x01, 0x01, 0x01, 0x05, 0x02, // 'J'
+    0x05, 0x05, 0x06, 0x05, 0x05, // 'K'
+    0x04, 0x04, 0x04, 0x04, 0x07, // 'L'
+    0x05, 0x07, 0x07, 0x05, 0x05, // 'M'
+    0x05, 0x07, 0x07, 0x07, 0x05, // 'N'
+    0x02, 0x05, 0x05, 0x05, 0x02, // 'O'
+    0x06, 0x05, 0x06, 0x04, 0x04, // 'P'
+    0x02, 0x05, 0x05, 0x07, 0x03, // 'Q'
+    0x06, 0x05, 0x07, 0x06, 0x05, // 'R'
+    0x03, 0x04, 0x02, 0x01, 0x06, // 'S'
+    0x07, 0x02, 0x02, 0x02, 0x02, // 'T'
+    0x05, 0x05, 0x05, 0x05, 0x03, // 'U'
+    0x05, 0x05, 0x05, 0x02, 0x02, // 'V'
+    0x05, 0x05, 0x07, 0x07, 0x05, // 'W'
+    0x05, 0x05, 0x02, 0x05, 0x05, // 'X'
+    0x05, 0x05, 0x02, 0x02, 0x02, // 'Y'
+    0x07, 0x01, 0x02, 0x04, 0x07, // 'Z'
+    0x07, 0x04, 0x04, 0x04, 0x07, // '['
+    0x04, 0x04, 0x02, 0x01, 0x01, // '\\'
+    0x07, 0x01, 0x01, 0x01, 0x07, // ']'
+    0x02, 0x05, 0x00, 0x00, 0x00, // '^'
+    0x00, 0x00, 0x00, 0x00, 0x07, // '_'
+    0x04, 0x02, 0x00, 0x00, 0x00, // '`'
+    0x00, 0x06, 0x03, 0x05, 0x07, // 'a'
+    0x04, 0x06, 0x05, 0x05, 0x06, // 'b'
+    0x00, 0x03, 0x04, 0x04, 0x03, // 'c'
+    0x01, 0x03, 0x05, 0x05, 0x03, // 'd'
+    0x00, 0x03, 0x05, 0x06, 0x03, // 'e'
+    0x01, 0x02, 0x07, 0x02, 0x02, // 'f'
+    0x00, 0x03, 0x05, 0x07, 0x01, // 'g'
+    0x04, 0x06, 0x05, 0x05, 0x05, // 'h'
+    0x02, 0x00, 0x02, 0x02, 0x02, // 'i'
+    0x01, 0x00, 0x01, 0x05, 0x02, // 'j'
+    0x04, 0x05, 0x06, 0x06, 0x05, // 'k'
+    0x06, 0x02, 0x02, 0x02, 0x07, // 'l'
+    0x00, 0x07, 0x07, 0x07, 0x05, // 'm'
+    0x00, 0x06, 0x05, 0x05, 0x05, // 'n'
+    0x00, 0x02, 0x05, 0x05, 0x02, // 'o'
+    0x00, 0x06, 0x05, 0x06, 0x04, // 'p'
+    0x00, 0x03, 0x05, 0x03, 0x01, // 'q'
+    0x00, 0x03, 0x04, 0x04, 0x04, // 'r'
+    0x00, 0x03, 0x06, 0x03, 0x06, // 's'
+    0x02, 0x07, 0x02, 0x02, 0x03, // 't'
+    0x00, 0x05, 0x05, 0x05, 0x03, // 'u'
+    0x00, 0x05, 0x05, 0x07, 0x02, // 'v'
+    0x00, 0x05, 0x07, 0x07, 0x07, // 'w'
+    0x00, 0x05, 0x02, 0x02, 0x05, // 'x'
+    0x00, 0x05, 0x05, 0x03, 0x06, // 'y'
+    0x00, 0x07, 0x03, 0x06, 0x07, // 'z'
+    0x03, 0x02, 0x06, 0x02, 0x03, // '{'
+    0x02, 0x02, 0x02, 0x02, 0x02, // '|'
+    0x06, 0x02, 0x03, 0x02, 0x06, // '}'
+    0x03, 0x06, 0x00, 0x00, 0x00, // '~'
+];
+
+#[rustfmt::skip]
+const GLYPHS_6X8: [u8; GLYPH_COUNT * 7] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // ' '
+    0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04, // '!'
+    0x0A, 0x0A, 0x0A, 0x00, 0x00, 0x00, 0x00, // '"'
+    0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A, // '#'
+    0x04, 0x0F, 0x14, 0x0E, 0x05, 0x1E, 0x04, // '$'
+    0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03, // '%'
+    0x0C, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0D, // '&'
+    0x04, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00, // '\''
+    0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02, // '('
+    0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08, // ')'
+    0x00, 0x04, 0x15, 0x0E, 0x15, 0x04, 0x00, // '*'
+    0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00, // '+'
+    0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08, // ','
+    0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00, // '-'
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C, // '.'
+    0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00, // '/'
+    0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E, // '0'
+    0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E, // '1'
+    0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F, // '2'
+    0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E, // '3'
+    0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02, // '4'
+    0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E, // '5'
+    0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E, // '6'
+    0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08, // '7'
+    0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E, // '8'
+    0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C, // '9'
+    0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00, // ':'
+    0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x04, 0x08, // ';'
+    0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02, // '<'
+    0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00, // '='
+    0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08, // '>'
+    0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04, // '?'
+    0x0E, 0x11, 0x01, 0x0D, 0x15, 0x15, 0x0E, // '@'
+    0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11, // 'A'
+    0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E, // 'B'
+    0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E, // 'C'
+    0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C, // 'D'
+    0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F, // 'E'
+    0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10, // 'F'
+    0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F, // 'G'
+    0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11, // 'H'
+    0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E, // 'I'
+    0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C, // 'J'
+    0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11, // 'K'
+    0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F, // 'L'
+    0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11, // 'M'
+    0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11, // 'N'
+    0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E, // 'O'
+    0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10, // 'P'
+    0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D, // 'Q'
+    0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11, // 'R'
+    0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E, // 'S'
+    0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, // 'T'
+    0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E, // 'U'
+    0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04, // 'V'
+    0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A, // 'W'
+    0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11, // 'X'
+    0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04, // 'Y'
+    0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F, // 'Z'
+    0x0E, 0x08, 0x08, 0x08, 0x08, 0x08, 0x0E, // '['
+    0x00, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00, // '\\'
+    0x0E, 0x02, 0x02, 0x02, 0x02, 0x02, 0x0E, // ']'
+    0x04, 0x0A, 0x11, 0x00, 0x00, 0x00, 0x00, // '^'
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F, // '_'
+    0x08, 0x04, 0x02, 0x00, 0x00, 0x00, 0x00, // '`'
+    0x00, 0x00, 0x0E, 0x01, 0x0F, 0x11, 0x0F, // 'a'
+    0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x1E, // 'b'
+    0x00, 0x00, 0x0E, 0x10, 0x10, 0x11, 0x0E, // 'c'
+    0x01, 0x01, 0x0D, 0x13, 0x11, 0x11, 0x0F, // 'd'
+    0x00, 0x00, 0x0E, 0x11, 0x1F, 0x10, 0x0E, // 'e'
+    0x06, 0x09, 0x08, 0x1C, 0x08, 0x08, 0x08, // 'f'
+    0x00, 0x0F, 0x11, 0x11, 0x0F, 0x01, 0x0E, // 'g'
+    0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x11, // 'h'
+    0x04, 0x00, 0x0C, 0x04, 0x04, 0x04, 0x0E, // 'i'
+    0x02, 0x00, 0x06, 0x02, 0x02, 0x12, 0x0C, // 'j'
+    0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12, // 'k'
+    0x0C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E, // 'l'
+    0x00, 0x00, 0x1A, 0x15, 0x15, 0x11, 0x11, // 'm'
+    0x00, 0x00, 0x16, 0x19, 0x11, 0x11, 0x11, // 'n'
+    0x00, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x0E, // 'o'
+    0x00, 0x00, 0x1E, 0x11, 0x1E, 0x10, 0x10, // 'p'
+    0x00, 0x00, 0x0D, 0x13, 0x0F, 0x01, 0x01, // 'q'
+    0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10, // 'r'
+    0x00, 0x00, 0x0E, 0x10, 0x0E, 0x01, 0x1E, // 's'
+    0x08, 0x08, 0x1C, 0x08, 0x08, 0x09, 0x06, // 't'
+    0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0D, // 'u'
+    0x00, 0x00, 0x11, 0x11, 0x11, 0x0A, 0x04, // 'v'
+    0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0A, // 'w'
+    0x00, 0x00, 0x11, 0x0A, 0x04, 0x0A, 0x11, // 'x'
+    0x00, 0x00, 0x11, 0x11, 0x0F, 0x01, 0x0E, // 'y'
+    0x00, 0x00, 0x1F, 0x02, 0x04, 0x08, 0x1F, // 'z'
+    0x02, 0x04, 0x04, 0x08, 0x04, 0x04, 0x02, // '{'
+    0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, // '|'
+    0x08, 0x04, 0x04, 0x02, 0x04, 0x04, 0x08, // '}'
+    0x00, 0x00, 0x08, 0x15, 0x02, 0x00, 0x00, // '~'
+];
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use crate::graphics::{Argb, Pixel, image::Image};
+
+const MAX_COLORS: usize = 256;
+const MIN_CODE_SIZE: u8 = 8;
+const MAX_CODE: u16 = 1 << 12;
+
+/// Colors are bucketed to 5 bits per channel before quantizing.
+fn bin(c: Argb) -> usize {
+    let [_, r, g, b] = c.decompose();
+    ((r as usize >> 3) << 10) | ((g as usize >> 3) << 5) | (b as usize >> 3)
+}
+
+fn bin_channel(bin: u16, channel: usize) -> u16 {
+    (bin >> (10 - channel * 5)) & 0x1F
+}
+
+/// Median cut over the bucketed colors of `image`. Returns the
+/// palette and the palette index of every pixel.
+fn quantize(image: &Image) -> (Vec<[u8; 3]>, Vec<u8>) {
+    let (w, h) = (image.width(), image.height());
+
+    // Pixel count and channel sums of every bucket.
+    let mut hist = vec![0u32; 1 << 15];
+    let mut sums = vec![[0u32; 3]; 1 << 15];
+
+    for y in 0..h {
+        for x in 0..w {
+            let c = image.pixel(x, y);
+            let [_, r, g, b] = c.decompose();
+            let i = bin(c);
+
+            hist[i] += 1;
+            sums[i][0] += r as u32;
+            sums[i][1] += g as u32;
+            sums[i][2] += b as u32;
+        }
+    }
+
+    let mut colors: Vec<u16> = (0..hist.len() as u16)
+        .filter(|&b| hist[b as usize] > 0)
+        .collect();
+
+    let mut boxes = Vec::with_capacity(MAX_COLORS);
+    boxes.push(0..colors.len());
+
+    while boxes.len() < MAX_COLORS {
+        // Split the box that is widest along any channel.
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.len() > 1)
+            .flat_map(|(i, r)| {
+                let colors = &colors[r.clone()];
+                (0..3).map(move |ch| {
+                    let (lo, hi) = colors.iter().fold((u16::MAX, 0), |(lo, hi), &c| {
+                        let v = bin_channel(c, ch);
+                        (lo.min(v), hi.max(v))
+                    });
+                    (hi - lo, i, ch)
+                })
+            })
+            .max_by_key(|&(range, ..)| range);
+
+        let Some((_, i, ch)) = widest else {
+            break;
+        };
+
+        let range = boxes[i].clone();
+        let slice = &mut colors[range.clone()];
+        slice.sort_unstable_by_key(|&c| bin_channel(c, ch));
+
+        // Split at the median by pixel count.
+        let total: u32 = slice.iter().map(|&c| hist[c as usize]).sum();
+        let mut acc = 0;
+        let mut mid = 1;
+
+        for (j, &c) in slice.iter().enumerate().take(slice.len() - 1) {
+            acc += hist[c as usize];
+            mid = j + 1;
+
+            if acc * 2 >= total {
+                break;
+            }
+        }
+
+        boxes[i] = range.start..range.start + mid;
+        boxes.push(range.start + mid..range.end);
+    }
+
+    let mut lut = vec![0u8; 1 << 15];
+
+    let palette = boxes
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            let mut sum = [0u64; 3];
+            let mut count = 0u64;
+
+            for &c in &colors[r.clone()] {
+                lut[c as usize] = i as u8;
+                count += hist[c as usize] as u64;
+
+                for (s, &bs) in sum.iter_mut().zip(&sums[c as usize]) {
+                    *s += bs as u64;
+                }
+            }
+
+            sum.map(|s| ((s + count / 2) / count.max(1)) as u8)
+        })
+        .collect();
+
+    let indices = (0..h)
+        .flat_map(|y| (0..w).map(move |x| (x, y)))
+        .map(|(x, y)| lut[bin(image.pixel(x, y))])
+        .collect();
+
+    (palette, indices)
+}
+
+/// Packs variable width codes, least significant bit first.
+struct BitWriter {
+    out: Vec<u8>,
+    acc: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.acc |= (code as u32) << self.bits;
+        self.bits += size;
+
+        while self.bits >= 8 {
+            self.out.push(self.acc as u8);
+            self.acc >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.out.push(self.acc as u8);
+        }
+
+        self.out
+    }
+}
+
+fn lzw(indices: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << MIN_CODE_SIZE;
+    let end = clear + 1;
+
+    let mut w = BitWriter {
+        out: Vec::new(),
+        acc: 0,
+        bits: 0,
+    };
+
+    let mut dict: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = clear + 2;
+    let mut size = MIN_CODE_SIZE + 1;
+
+    w.write(clear, size);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        w.write(end, size);
+        return w.finish();
+    };
+
+    let mut prefix = first as u16;
+
+    // The decoder lags one code behind, so the width
+    // only grows once `next` no longer fits.
+    let grow = |next: u16, size: &mut u8| {
+        if next > (1 << *size) - 1 && *size < 12 {
+            *size += 1;
+        }
+    };
+
+    for &k in rest {
+        if let Some(&code) = dict.get(&(prefix, k)) {
+            prefix = code;
+            continue;
+        }
+
+        w.write(prefix, size);
+        grow(next, &mut size);
+
+        if next < MAX_CODE {
+            dict.insert((prefix, k), next);
+            next += 1;
+        } else {
+            w.write(clear, size);
+            dict.clear();
+            next = clear + 2;
+            size = MIN_CODE_SIZE + 1;
+        }
+
+        prefix = k as u16;
+    }
+
+    w.write(prefix, size);
+    grow(next, &mut size);
+
+    w.write(end, size);
+    w.finish()
+}
+
+/// Splits `data` into sub-blocks of up to 255 bytes.
+fn write_blocks(out: &mut Vec<u8>, data: &[u8]) {
+    for chunk in data.chunks(255) {
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+    }
+
+    out.push(0);
+}
+
+/// Encodes a looping animated GIF. Each frame gets its own palette
+/// and is shown for its delay, in hundredths of a second.
+pub fn encode(frames: &[(Image, u16)]) -> Vec<u8> {
+    let (w, h) = frames
+        .first()
+        .map_or((0, 0), |(f, _)| (f.width() as u16, f.height() as u16));
+
+    let mut out = Vec::new();
+
+    out.extend_from_slice(b"GIF89a");
+    out.extend_from_slice(&w.to_le_bytes());
+    out.extend_from_slice(&h.to_le_bytes());
+    out.extend_from_slice(&[0, 0, 0]);
+
+    // Loop forever.
+    out.extend_from_slice(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    for (image, delay) in frames {
+        let (palette, indices) = quantize(image);
+
+        // Graphic control: keep the frame, no transparency.
+        out.extend_from_slice(&[0x21, 0xF9, 0x04, 0x04]);
+        out.extend_from_slice(&delay.to_le_bytes());
+        out.extend_from_slice(&[0, 0]);
+
+        out.push(0x2C);
+        out.extend_from_slice(&[0, 0, 0, 0]);
+        out.extend_from_slice(&(image.width() as u16).to_le_bytes());
+        out.extend_from_slice(&(image.height() as u16).to_le_bytes());
+
+        // A local color table of 256 entries.
+        out.push(0x80 | 0x07);
+
+        for i in 0..MAX_COLORS {
+            out.extend_from_slice(&palette.get(i).copied().unwrap_or_default());
+        }
+
+        out.push(MIN_CODE_SIZE);
+        write_blocks(&mut out, &lzw(&indices));
+    }
+
+    out.push(0x3B);
+    out
+}
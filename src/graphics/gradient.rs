@@ -1,12 +1,206 @@
-use crate::graphics::P2;
+use arrayvec::ArrayVec;
 
-enum GradientType {
-    Raidal(P2, f32),
-    Linear(P2, f32),
+use std::f32::consts::TAU;
+
+use crate::graphics::{Argb, P2, Pixel};
+
+pub const MAX_STOPS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientType {
+    /// Runs from the first point (t = 0) to the second (t = 1).
+    Linear(P2, P2),
+
+    /// Grows from the center out to the radius.
+    #[allow(dead_code)]
+    Radial(P2, f32),
+
+    /// Sweeps clockwise around the center, starting
+    /// at the given angle in radians.
+    #[allow(dead_code)]
+    Conic(P2, f32),
 }
 
-struct Gradient {
+#[derive(Debug, Clone)]
+pub struct Gradient {
     gradient_type: GradientType,
-    color_a: u32,
-    color_b: u32,
+    stops: ArrayVec<(f32, Argb), MAX_STOPS>,
+}
+
+pub fn interpolate(c1: Argb, c2: Argb, t: f32) -> Argb {
+    let a = c1.decompose();
+    let b = c2.decompose();
+
+    Argb::compose(std::array::from_fn(|i| {
+        (a[i] as f32 + (b[i] as f32 - a[i] as f32) * t) as u8
+    }))
+}
+
+impl Gradient {
+    pub fn new(gradient_type: GradientType) -> Self {
+        Self {
+            gradient_type,
+            stops: ArrayVec::new(),
+        }
+    }
+
+    pub fn linear(start: P2, end: P2) -> Self {
+        Self::new(GradientType::Linear(start, end))
+    }
+
+    #[allow(dead_code)]
+    pub fn radial(center: P2, radius: f32) -> Self {
+        Self::new(GradientType::Radial(center, radius))
+    }
+
+    #[allow(dead_code)]
+    pub fn conic(center: P2, angle: f32) -> Self {
+        Self::new(GradientType::Conic(center, angle))
+    }
+
+    /// Adds a color stop at `t` (0..=1). Stops should be added
+    /// in increasing order, extra stops beyond `MAX_STOPS` are ignored.
+    pub fn stop(mut self, t: f32, color: Argb) -> Self {
+        let _ = self.stops.try_push((t.clamp(0.0, 1.0), color));
+        self
+    }
+
+    /// Position of `p` along the gradient, in 0..=1.
+    pub fn position(&self, p: P2) -> f32 {
+        let t = match self.gradient_type {
+            GradientType::Linear(a, b) => {
+                let d = P2(b.0 - a.0, b.1 - a.1);
+                let len2 = (d.0 * d.0 + d.1 * d.1) as f32;
+
+                if len2 == 0.0 {
+                    return 0.0;
+                }
+
+                ((p.0 - a.0) * d.0 + (p.1 - a.1) * d.1) as f32 / len2
+            }
+
+            GradientType::Radial(c, r) => {
+                let d = P2(p.0 - c.0, p.1 - c.1).to_cplx();
+                d.mag() / r.max(f32::EPSILON)
+            }
+
+            GradientType::Conic(c, angle) => {
+                let d = P2(p.0 - c.0, p.1 - c.1).to_cplx();
+                ((d.1.atan2(d.0) - angle) / TAU).rem_euclid(1.0)
+            }
+        };
+
+        t.clamp(0.0, 1.0)
+    }
+
+    /// Color at `t` (0..=1) between the stops.
+    pub fn color_at(&self, t: f32) -> Argb {
+        let Some(&(first_t, first_c)) = self.stops.first() else {
+            return Argb::white();
+        };
+
+        if t <= first_t {
+            return first_c;
+        }
+
+        for pair in self.stops.windows(2) {
+            let [(t1, c1), (t2, c2)] = [pair[0], pair[1]];
+
+            if t <= t2 {
+                let span = t2 - t1;
+
+                if span <= 0.0 {
+                    return c2;
+                }
+
+                return interpolate(c1, c2, (t - t1) / span);
+            }
+        }
+
+        self.stops[self.stops.len() - 1].1
+    }
+
+    pub fn sample(&self, p: P2) -> Argb {
+        self.color_at(self.position(p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Argb = 0xFF_00_00_00;
+    const WHITE: Argb = 0xFF_FF_FF_FF;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn linear_position() {
+        let g = Gradient::linear(P2(0, 10), P2(0, 0));
+
+        assert!(close(g.position(P2(5, 10)), 0.0));
+        assert!(close(g.position(P2(0, 5)), 0.5));
+        assert!(close(g.position(P2(-3, 0)), 1.0));
+
+        // Clamped past both ends.
+        assert!(close(g.position(P2(0, 20)), 0.0));
+        assert!(close(g.position(P2(0, -20)), 1.0));
+
+        assert!(close(
+            Gradient::linear(P2(3, 3), P2(3, 3)).position(P2(9, 9)),
+            0.0
+        ));
+    }
+
+    #[test]
+    fn radial_position() {
+        let g = Gradient::radial(P2(10, 10), 10.0);
+
+        assert!(close(g.position(P2(10, 10)), 0.0));
+        assert!(close(g.position(P2(15, 10)), 0.5));
+        assert!(close(g.position(P2(10, 0)), 1.0));
+        assert!(close(g.position(P2(40, 40)), 1.0));
+    }
+
+    #[test]
+    fn conic_position() {
+        let g = Gradient::conic(P2(0, 0), 0.0);
+
+        // y grows downward, so a quarter turn clockwise is +y.
+        assert!(close(g.position(P2(10, 0)), 0.0));
+        assert!(close(g.position(P2(0, 10)), 0.25));
+        assert!(close(g.position(P2(-10, 0)), 0.5));
+        assert!(close(g.position(P2(0, -10)), 0.75));
+
+        let g = Gradient::conic(P2(0, 0), TAU / 4.0);
+        assert!(close(g.position(P2(0, 10)), 0.0));
+        assert!(close(g.position(P2(10, 0)), 0.75));
+    }
+
+    #[test]
+    fn color_between_stops() {
+        assert_eq!(Gradient::linear(P2(0, 0), P2(1, 0)).color_at(0.5), WHITE);
+
+        let g = Gradient::radial(P2(0, 0), 1.0)
+            .stop(0.25, BLACK)
+            .stop(0.75, WHITE);
+
+        assert_eq!(g.color_at(0.0), BLACK);
+        assert_eq!(g.color_at(0.25), BLACK);
+        assert_eq!(g.color_at(0.5), 0xFF_7F_7F_7F);
+        assert_eq!(g.color_at(0.75), WHITE);
+        assert_eq!(g.color_at(1.0), WHITE);
+
+        // A hard edge where two stops meet.
+        let g = Gradient::conic(P2(0, 0), 0.0)
+            .stop(0.0, BLACK)
+            .stop(0.5, BLACK)
+            .stop(0.5, WHITE)
+            .stop(1.0, WHITE);
+
+        assert_eq!(g.sample(P2(0, 10)), BLACK);
+        assert_eq!(g.sample(P2(0, -10)), WHITE);
+    }
 }
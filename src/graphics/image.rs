@@ -0,0 +1,380 @@
+use std::io::Cursor;
+use std::path::Path;
+
+use crate::data::log::error;
+use crate::graphics::{Argb, P2, Painter, Pixel};
+use crate::math::Cplx;
+
+const QOI_MAGIC: &[u8] = b"qoif";
+const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// An image in straight (not premultiplied) ARGB.
+#[derive(Debug, Clone)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    data: Vec<Argb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, data: Vec<Argb>) -> Self {
+        assert_eq!(
+            data.len(),
+            width * height,
+            "Image data does not match its size."
+        );
+
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    fn from_rgba(width: usize, height: usize, rgba: &[u8]) -> Self {
+        let data = rgba
+            .chunks_exact(4)
+            .map(|p| Argb::compose([p[3], p[0], p[1], p[2]]))
+            .collect();
+
+        Self::new(width, height, data)
+    }
+
+    pub fn from_qoi(bytes: &[u8]) -> Option<Self> {
+        let mut decoder = qoi::Decoder::new(bytes)
+            .map(|d| d.with_channels(qoi::Channels::Rgba))
+            .inspect_err(|e| error!("Failed to read QOI header: {}", e))
+            .ok()?;
+
+        let qoi::Header { width, height, .. } = *decoder.header();
+
+        let rgba = decoder
+            .decode_to_vec()
+            .inspect_err(|e| error!("Failed to decode QOI image: {}", e))
+            .ok()?;
+
+        Some(Self::from_rgba(width as usize, height as usize, &rgba))
+    }
+
+    pub fn from_png(bytes: &[u8]) -> Option<Self> {
+        let mut decoder = png::Decoder::new(Cursor::new(bytes));
+
+        // Palettes are expanded, 16 bit channels are cut down to 8.
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+
+        let mut reader = decoder
+            .read_info()
+            .inspect_err(|e| error!("Failed to read PNG header: {}", e))
+            .ok()?;
+
+        let mut buf = vec![0u8; reader.output_buffer_size()?];
+
+        let info = reader
+            .next_frame(&mut buf)
+            .inspect_err(|e| error!("Failed to decode PNG image: {}", e))
+            .ok()?;
+
+        let width = info.width as usize;
+        let height = info.height as usize;
+        let buf = &buf[..info.buffer_size()];
+
+        let data = match info.color_type {
+            png::ColorType::Rgba => return Some(Self::from_rgba(width, height, buf)),
+
+            png::ColorType::Rgb => buf
+                .chunks_exact(3)
+                .map(|p| Argb::compose([0xFF, p[0], p[1], p[2]]))
+                .collect(),
+
+            png::ColorType::GrayscaleAlpha => buf
+                .chunks_exact(2)
+                .map(|p| Argb::compose([p[1], p[0], p[0], p[0]]))
+                .collect(),
+
+            png::ColorType::Grayscale => buf
+                .iter()
+                .map(|&l| Argb::compose([0xFF, l, l, l]))
+                .collect(),
+
+            png::ColorType::Indexed => {
+                error!("Unexpected indexed PNG after expansion.");
+                return None;
+            }
+        };
+
+        Some(Self::new(width, height, data))
+    }
+
+    /// Decodes a QOI or PNG image, telling them apart by their magic bytes.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(QOI_MAGIC) {
+            Self::from_qoi(bytes)
+        } else if bytes.starts_with(PNG_MAGIC) {
+            Self::from_png(bytes)
+        } else {
+            error!("Unsupported image format, only QOI and PNG can be loaded.");
+            None
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Option<Self> {
+        let path = path.as_ref();
+
+        let bytes = std::fs::read(path)
+            .inspect_err(|e| {
+                let path = path.display();
+                error!("Failed to read {}: {}", path, e)
+            })
+            .ok()?;
+
+        Self::decode(&bytes)
+    }
+
+    fn to_rgba(&self) -> Vec<u8> {
+        self.data
+            .iter()
+            .flat_map(|c| {
+                let [a, r, g, b] = c.decompose();
+                [r, g, b, a]
+            })
+            .collect()
+    }
+
+    pub fn encode_qoi(&self) -> Option<Vec<u8>> {
+        qoi::encode_to_vec(self.to_rgba(), self.width as u32, self.height as u32)
+            .inspect_err(|e| error!("Failed to encode QOI image: {}", e))
+            .ok()
+    }
+
+    pub fn encode_png(&self) -> Option<Vec<u8>> {
+        let mut out = Vec::new();
+
+        let mut encoder = png::Encoder::new(&mut out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        encoder
+            .write_header()
+            .and_then(|mut w| w.write_image_data(&self.to_rgba()))
+            .inspect_err(|e| error!("Failed to encode PNG image: {}", e))
+            .ok()?;
+
+        Some(out)
+    }
+
+    /// Writes a QOI image if the path ends in `.qoi`, PNG otherwise.
+    pub fn save(&self, path: impl AsRef<Path>) -> Option<()> {
+        let path = path.as_ref();
+
+        let bytes = match path.extension() {
+            Some(e) if e.eq_ignore_ascii_case("qoi") => self.encode_qoi()?,
+            _ => self.encode_png()?,
+        };
+
+        std::fs::write(path, bytes)
+            .inspect_err(|e| {
+                let path = path.display();
+                error!("Failed to write {}: {}", path, e)
+            })
+            .ok()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn size(&self) -> P2 {
+        P2(self.width as i32, self.height as i32)
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Argb {
+        self.data[y * self.width + x]
+    }
+
+    fn sample_nearest(&self, u: f32, v: f32) -> Argb {
+        let x = (u as usize).min(self.width - 1);
+        let y = (v as usize).min(self.height - 1);
+        self.pixel(x, y)
+    }
+
+    /// Bilinear sample, clamping to the edges. `u` and `v`
+    /// are in pixels with pixel centers at half integers.
+    fn sample_bilinear(&self, u: f32, v: f32) -> Argb {
+        let u = (u - 0.5).clamp(0.0, (self.width - 1) as f32);
+        let v = (v - 0.5).clamp(0.0, (self.height - 1) as f32);
+
+        let (x0, y0) = (u as usize, v as usize);
+        let x1 = (x0 + 1).min(self.width - 1);
+        let y1 = (y0 + 1).min(self.height - 1);
+
+        let fx = u - x0 as f32;
+        let fy = v - y0 as f32;
+
+        let p = [
+            self.pixel(x0, y0).decompose(),
+            self.pixel(x1, y0).decompose(),
+            self.pixel(x0, y1).decompose(),
+            self.pixel(x1, y1).decompose(),
+        ];
+
+        Argb::compose(std::array::from_fn(|i| {
+            let top = p[0][i] as f32 + (p[1][i] as f32 - p[0][i] as f32) * fx;
+            let bottom = p[2][i] as f32 + (p[3][i] as f32 - p[2][i] as f32) * fx;
+            (top + (bottom - top) * fy + 0.5) as u8
+        }))
+    }
+}
+
+/// Rounded channel multiply, so that 255 * 255 stays 255.
+fn channel_mul(a: u8, b: u8) -> u8 {
+    ((a as u16 * b as u16 + 127) / 255) as u8
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    Nearest,
+    Bilinear,
+}
+
+/// How an image is drawn by `Painter::blit`.
+#[derive(Debug, Clone, Copy)]
+pub struct Blit {
+    pub filter: Filter,
+
+    /// Clockwise rotation around the center, in radians.
+    pub angle: f32,
+
+    /// Multiplied into the color channels, white leaves them as is.
+    pub tint: Argb,
+
+    /// Multiplied into the alpha of the image.
+    pub alpha: u8,
+}
+
+impl Default for Blit {
+    fn default() -> Self {
+        Self {
+            filter: Filter::Nearest,
+            angle: 0.0,
+            tint: Argb::white(),
+            alpha: 0xFF,
+        }
+    }
+}
+
+impl Blit {
+    pub fn filter(self, filter: Filter) -> Self {
+        Self { filter, ..self }
+    }
+
+    #[allow(dead_code)]
+    pub fn rotate(self, angle: f32) -> Self {
+        Self { angle, ..self }
+    }
+
+    pub fn tint(self, tint: Argb) -> Self {
+        Self { tint, ..self }
+    }
+
+    #[allow(dead_code)]
+    pub fn alpha(self, alpha: u8) -> Self {
+        Self { alpha, ..self }
+    }
+}
+
+impl<'a> Painter<'a> {
+    /// Copies the canvas at its physical size. Alpha is
+    /// forced to opaque since the window ignores it as well.
+    pub fn to_image(&self) -> Image {
+        let data = self.buffer.iter().map(|c| c.set_alpha(0xFF)).collect();
+        Image::new(self.width, self.height, data)
+    }
+
+    /// Copies the canvas at the resolution visualizers draw at.
+    pub fn to_logical_image(&self) -> Image {
+        let (width, height) = self.logical_sizeu();
+
+        let mut data = Vec::with_capacity(width * height);
+        self.read_logical(&mut data);
+        data.iter_mut().for_each(|c| *c = c.set_alpha(0xFF));
+
+        Image::new(width, height, data)
+    }
+
+    /// Draws `image` stretched to `size` logical pixels, centered
+    /// on `center`. Fully transparent pixels are skipped, partial
+    /// alpha is handed to the mixer, so pair this with `mixerm`
+    /// for images that are not opaque.
+    pub fn blit(&mut self, image: &Image, center: Cplx, size: Cplx, blit: Blit) {
+        if image.width == 0 || image.height == 0 || size.0 <= 0.0 || size.1 <= 0.0 {
+            return;
+        }
+
+        let (sin, cos) = blit.angle.sin_cos();
+        let half = size * 0.5;
+
+        // Bounding box of the rotated image.
+        let extent = Cplx(
+            (half.0 * cos).abs() + (half.1 * sin).abs(),
+            (half.0 * sin).abs() + (half.1 * cos).abs(),
+        );
+
+        let P2(w, h) = self.logical_size();
+        let x0 = ((center.0 - extent.0).floor() as i32).max(0);
+        let y0 = ((center.1 - extent.1).floor() as i32).max(0);
+        let x1 = ((center.0 + extent.0).ceil() as i32 + 1).min(w);
+        let y1 = ((center.1 + extent.1).ceil() as i32 + 1).min(h);
+
+        let sx = image.width as f32 / size.0;
+        let sy = image.height as f32 / size.1;
+
+        let [_, tr, tg, tb] = blit.tint.decompose();
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                // Sample at the pixel center, rotated back into image space.
+                let d = Cplx(x as f32 + 0.5, y as f32 + 0.5) - center;
+                let local = Cplx(d.0 * cos + d.1 * sin, d.1 * cos - d.0 * sin) + half;
+
+                if local.0 < 0.0 || local.1 < 0.0 || local.0 >= size.0 || local.1 >= size.1 {
+                    continue;
+                }
+
+                let (u, v) = (local.0 * sx, local.1 * sy);
+
+                let c = match blit.filter {
+                    Filter::Nearest => image.sample_nearest(u, v),
+                    Filter::Bilinear => image.sample_bilinear(u, v),
+                };
+
+                let [a, r, g, b] = c.decompose();
+                let a = channel_mul(a, blit.alpha);
+
+                if a == 0 {
+                    continue;
+                }
+
+                let c = Argb::compose([
+                    a,
+                    channel_mul(r, tr),
+                    channel_mul(g, tg),
+                    channel_mul(b, tb),
+                ]);
+                self.plot_color(P2(x, y), c);
+            }
+        }
+    }
+
+    /// Draws `image` scaled to cover the whole canvas,
+    /// cropping whatever does not fit the aspect ratio.
+    pub fn blit_cover(&mut self, image: &Image, blit: Blit) {
+        let size = self.logical_size().to_cplx();
+        let fit = (size.0 / image.width as f32).max(size.1 / image.height as f32);
+
+        self.blit(image, size * 0.5, image.size().to_cplx() * fit, blit);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::graphics::image::Image;
+
+/// Kitty only takes this much base64 per escape sequence.
+const CHUNK: usize = 4096;
+
+/// Id of the one image coffeevis shows, replaced every frame.
+const IMAGE_ID: u32 = 0xC0FE;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - i * 6)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
+
+/// Encodes `image` as a PNG sent through the kitty graphics protocol,
+/// drawn at the cursor without moving it. Responses are suppressed so
+/// that they do not show up as key presses.
+pub fn encode(image: &Image) -> Option<String> {
+    let data = base64(&image.encode_png()?);
+    let mut out = String::with_capacity(data.len() + data.len() / CHUNK * 8 + 64);
+
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(CHUNK).collect();
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = (i + 1 < chunks.len()) as u8;
+
+        if i == 0 {
+            out.push_str(&format!(
+                "\x1b_Ga=T,f=100,i={IMAGE_ID},p=1,q=2,C=1,m={more};"
+            ));
+        } else {
+            out.push_str(&format!("\x1b_Gm={more};"));
+        }
+
+        // Always ASCII.
+        out.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        out.push_str("\x1b\\");
+    }
+
+    Some(out)
+}
+
+/// Removes the image from the screen.
+pub fn delete() -> String {
+    format!("\x1b_Ga=d,d=I,i={IMAGE_ID},q=2\x1b\\")
+}
@@ -0,0 +1,186 @@
+use crate::graphics::{Argb, P2, Painter, Pixel, blend::BlendMode};
+
+/// A canvas of its own, composited onto the output every frame.
+///
+/// Opaque layers ignore alpha and keep whatever was drawn on them,
+/// like the canvas visualizers used to draw on directly. Transparent
+/// layers keep their alpha (straight, not premultiplied) and are
+/// drawn with mixers that preserve it.
+#[derive(Clone)]
+pub struct Layer {
+    buffer: Vec<Argb>,
+    width: usize,
+    height: usize,
+    opaque: bool,
+
+    pub blend: BlendMode,
+    pub opacity: u8,
+}
+
+impl Layer {
+    fn new(opaque: bool) -> Self {
+        Self {
+            buffer: Vec::new(),
+            width: 0,
+            height: 0,
+            opaque,
+            blend: BlendMode::Normal,
+            opacity: 0xFF,
+        }
+    }
+
+    pub fn opaque() -> Self {
+        Self::new(true)
+    }
+
+    pub fn transparent() -> Self {
+        Self::new(false)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn as_slice(&self) -> &[Argb] {
+        &self.buffer
+    }
+
+    /// Whether compositing this layer hides everything below.
+    pub fn covers(&self) -> bool {
+        self.opaque && self.opacity == 0xFF && self.blend == BlendMode::Normal
+    }
+
+    /// Resizes the layer, clearing it if the size changed.
+    /// Returns whether it did.
+    pub fn fit(&mut self, width: usize, height: usize) -> bool {
+        if (self.width, self.height) == (width, height) {
+            return false;
+        }
+
+        self.width = width;
+        self.height = height;
+        self.buffer.clear();
+        self.buffer.resize(width * height, 0);
+
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.fill(0);
+    }
+
+    /// A painter over this layer with the scale, field and theme of `like`,
+    /// which does not have to be the same size.
+    /// Transparent layers are drawn on every row, since they are
+    /// cleared every frame.
+    pub fn painter(&mut self, like: &Painter) -> Painter<'_> {
+        self.painter_with(like, like.fill || !self.opaque)
+    }
+
+    /// Like `painter`, but on every row whatever the field of `like`,
+    /// for layers that are drawn once and kept.
+    pub fn painter_full(&mut self, like: &Painter) -> Painter<'_> {
+        self.painter_with(like, true)
+    }
+
+    fn painter_with(&mut self, like: &Painter, fill: bool) -> Painter<'_> {
+        let field = if fill { 0 } else { like.field };
+
+        let mut pix = Painter::from(
+            &mut self.buffer,
+            self.width,
+            self.height,
+            like.scale,
+            field,
+            fill,
+        );
+
+        pix.set_theme(like.theme);
+        pix.transparent = !self.opaque;
+
+        if pix.transparent {
+            pix.mixerm();
+        }
+
+        pix
+    }
+}
+
+impl<'a> Painter<'a> {
+    /// Composites `layer` onto the canvas with its blend mode and
+    /// opacity, its top left corner at `origin` in physical pixels.
+    pub fn composite(&mut self, layer: &Layer, origin: P2) {
+        let (ox, oy) = (origin.0.max(0) as usize, origin.1.max(0) as usize);
+
+        if layer.opacity == 0 || ox >= self.width || oy >= self.height {
+            return;
+        }
+
+        let w = layer.width.min(self.width - ox);
+        let h = layer.height.min(self.height - oy);
+
+        let mixer = layer.blend.mixer();
+        let opacity = layer.opacity as u16;
+
+        for y in 0..h {
+            let start = (oy + y) * self.width + ox;
+            let dst = &mut self.buffer[start..start + w];
+            let src = &layer.buffer[y * layer.width..y * layer.width + w];
+
+            if layer.covers() {
+                dst.copy_from_slice(src);
+                continue;
+            }
+
+            for (d, &s) in dst.iter_mut().zip(src) {
+                let a = if layer.opaque {
+                    opacity
+                } else {
+                    let [a, ..] = s.decompose();
+                    (a as u16 * opacity + 127) / 255
+                };
+
+                if a == 0 {
+                    continue;
+                }
+
+                *d = mixer(*d, s.set_alpha(a as u8));
+            }
+        }
+    }
+}
+
+/// The layers of every frame, bottom to top.
+pub struct LayerStack {
+    /// Background color and image.
+    pub background: Layer,
+
+    /// What visualizers draw on, kept between frames.
+    pub visualizer: Layer,
+
+    /// Text and other things that should not stick
+    /// around, cleared every frame.
+    pub overlay: Layer,
+}
+
+impl LayerStack {
+    pub fn new() -> Self {
+        Self {
+            background: Layer::opaque(),
+            visualizer: Layer::opaque(),
+            overlay: Layer::transparent(),
+        }
+    }
+
+    /// Returns whether any layer was resized.
+    pub fn fit(&mut self, width: usize, height: usize) -> bool {
+        let a = self.background.fit(width, height);
+        let b = self.visualizer.fit(width, height);
+        let c = self.overlay.fit(width, height);
+        a || b || c
+    }
+}
@@ -2,11 +2,45 @@ pub mod blend;
 
 pub mod draw;
 
-use blend::Mixer;
+pub mod font;
+
+pub mod gif;
+
+pub mod gradient;
+
+pub mod image;
+
+pub mod kitty;
+
+pub mod layer;
+
+pub mod palette;
+
+pub mod postfx;
+
+pub mod shapes;
+
+pub mod sixel;
+
+pub mod stroke;
+
+pub mod theme;
+
+pub mod transition;
+
+pub mod upscale;
+
+use blend::{BlendMode, Mixer};
+use font::{Font, TextAlign};
+use gradient::Gradient;
+use image::{Blit, Filter, Image};
+use stroke::Stroke;
+use theme::{THEMES, Theme};
 
 use std::ops;
+use std::rc::Rc;
 
-use crate::data::DEFAULT_BG_COLOR;
+use crate::data::BACKGROUND_IMAGE_TINT;
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum RenderEffect {
@@ -40,7 +74,6 @@ pub(crate) trait Pixel:
     + ops::Mul<Output = Self>
     + std::fmt::Debug
 {
-    fn black() -> Self;
     fn white() -> Self;
 
     fn over(self, other: Self) -> Self;
@@ -98,14 +131,24 @@ pub struct Painter<'a> {
     height: usize,
 
     color: Argb,
+    gradient: Option<Gradient>,
     mixer: Mixer,
+    stroke: Stroke,
+
+    font: Font,
+    text_align: TextAlign,
 
     scale: u8,
 
     field: u8,
     fill: bool,
 
+    /// Drawing on a layer that keeps its alpha.
+    transparent: bool,
+
+    theme: &'static Theme,
     background: Argb,
+    background_image: Option<Rc<Image>>,
 }
 
 impl<'a> Painter<'a> {
@@ -124,31 +167,61 @@ impl<'a> Painter<'a> {
             height,
 
             color: Argb::white(),
+            gradient: None,
             mixer: u32::over,
+            stroke: Stroke::default(),
+
+            font: Font::default(),
+            text_align: TextAlign::Left,
 
             scale,
 
             field,
             fill,
 
-            background: DEFAULT_BG_COLOR,
+            transparent: false,
+
+            theme: &THEMES[0],
+            background: THEMES[0].background,
+            background_image: None,
         }
     }
 
     pub fn color(&mut self, c: Argb) {
         self.color = c;
+        self.gradient = None;
+    }
+
+    /// Paints with a gradient instead of a solid color until
+    /// the next call to `color`. Points are in logical coordinates.
+    pub fn gradient(&mut self, g: Gradient) {
+        self.gradient = Some(g);
     }
 
     pub fn mixer(&mut self, mixer: Mixer) {
         self.mixer = mixer;
     }
 
+    /// Composites with one of the `BlendMode`s, which
+    /// respect the alpha of the color being drawn.
+    pub fn blend_mode(&mut self, mode: BlendMode) {
+        self.mixer = mode.mixer();
+    }
+
     pub fn mixerd(&mut self) {
         self.mixer = u32::over;
     }
 
     pub fn mixerm(&mut self) {
-        self.mixer = u32::mix;
+        self.mixer = if self.transparent {
+            blend::over_straight
+        } else {
+            u32::mix
+        };
+    }
+
+    pub fn scale_factor(&self) -> usize {
+        self.scale as usize
     }
 
     pub fn logical_width(&self) -> usize {
@@ -171,9 +244,27 @@ impl<'a> Painter<'a> {
         self.buffer.len()
     }
 
+    /// Image drawn by `clear` on top of the background color.
+    pub fn background_image(&mut self, image: Option<Rc<Image>>) {
+        self.background_image = image;
+    }
+
     pub fn clear(&mut self) {
         self.color(self.background);
         self.fill();
+
+        if let Some(image) = self.background_image.clone() {
+            let mixer = self.mixer;
+            self.blend_mode(BlendMode::Normal);
+
+            // Dimmed so the visualizer stays readable on top.
+            let blit = Blit::default()
+                .filter(Filter::Bilinear)
+                .tint(BACKGROUND_IMAGE_TINT);
+
+            self.blit_cover(&image, blit);
+            self.mixer = mixer;
+        }
     }
 
     pub fn pixel(&self, i: usize) -> Argb {
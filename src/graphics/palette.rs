@@ -0,0 +1,209 @@
+use crate::graphics::{Argb, Pixel};
+
+/// How many colors the terminal can show.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorDepth {
+    /// No colors at all, for `NO_COLOR` and dumb terminals.
+    Mono,
+    Ansi16,
+    Xterm256,
+    TrueColor,
+}
+
+/// xterm's defaults for the 16 standard colors. Terminals
+/// are free to change them, so this is only a best guess.
+const ANSI16: [u32; 16] = [
+    0x000000, 0xCD0000, 0x00CD00, 0xCDCD00, 0x0000EE, 0xCD00CD, 0x00CDCD, 0xE5E5E5, 0x7F7F7F,
+    0xFF0000, 0x00FF00, 0xFFFF00, 0x5C5CFF, 0xFF00FF, 0x00FFFF, 0xFFFFFF,
+];
+
+/// Levels of the 6x6x6 cube at 16 to 231.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Squared color distance, weighted roughly by how bright each channel looks.
+fn distance(a: Argb, b: Argb) -> u32 {
+    let [_, r1, g1, b1] = a.decompose();
+    let [_, r2, g2, b2] = b.decompose();
+
+    let d = |x: u8, y: u8| (x.abs_diff(y) as u32).pow(2);
+
+    3 * d(r1, r2) + 4 * d(g1, g2) + 2 * d(b1, b2)
+}
+
+fn cube_level(v: u8) -> usize {
+    match v {
+        0..48 => 0,
+        48..115 => 1,
+        v => (v as usize - 35) / 40,
+    }
+}
+
+impl ColorDepth {
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "mono" => Self::Mono,
+            "16" => Self::Ansi16,
+            "256" => Self::Xterm256,
+            "truecolor" | "24bit" => Self::TrueColor,
+            _ => return None,
+        })
+    }
+
+    /// Guesses from `NO_COLOR`, `COLORTERM` and the terminal name.
+    /// Terminals nothing is known about keep getting full colors.
+    pub fn detect() -> Self {
+        let var = |name| std::env::var(name).unwrap_or_default();
+
+        if !var("NO_COLOR").is_empty() {
+            return Self::Mono;
+        }
+
+        if matches!(var("COLORTERM").as_str(), "truecolor" | "24bit") {
+            return Self::TrueColor;
+        }
+
+        match var("TERM").as_str() {
+            "dumb" => Self::Mono,
+            t if t.contains("256color") => Self::Xterm256,
+            t if t.ends_with("16color") => Self::Ansi16,
+            "linux" | "screen" | "tmux" | "ansi" | "cons25" | "vt220" | "vt100" => Self::Ansi16,
+            _ => Self::TrueColor,
+        }
+    }
+
+    /// Index of the closest palette color, `None` when there is no palette.
+    pub fn quantize(self, c: Argb) -> Option<u8> {
+        match self {
+            Self::Ansi16 => (0..16u8).min_by_key(|&i| distance(c, ANSI16[i as usize])),
+
+            // The first 16 vary too much between terminals to be useful.
+            Self::Xterm256 => {
+                let [_, r, g, b] = c.decompose();
+                let cube = 16 + 36 * cube_level(r) + 6 * cube_level(g) + cube_level(b);
+
+                let avg = (r as usize + g as usize + b as usize) / 3;
+                let gray = 232 + (avg.saturating_sub(3) / 10).min(23);
+
+                [cube as u8, gray as u8]
+                    .into_iter()
+                    .min_by_key(|&i| distance(c, self.color(i)))
+            }
+
+            _ => None,
+        }
+    }
+
+    /// Color of a palette index.
+    pub fn color(self, i: u8) -> Argb {
+        match (self, i) {
+            (Self::Ansi16, _) | (_, 0..16) => ANSI16[i as usize % 16] | 0xFF00_0000,
+
+            (_, 16..232) => {
+                let i = i as usize - 16;
+                Argb::compose([0xFF, CUBE[i / 36], CUBE[i / 6 % 6], CUBE[i % 6]])
+            }
+
+            _ => {
+                let l = 8 + (i - 232) * 10;
+                Argb::compose([0xFF, l, l, l])
+            }
+        }
+    }
+
+    /// `c` as it will show up on the terminal.
+    pub fn snap(self, c: Argb) -> Argb {
+        match self.quantize(c) {
+            Some(i) => self.color(i),
+            None => c,
+        }
+    }
+}
+
+/// Dithering done before reducing a frame to a palette.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dither {
+    None,
+    /// 4x4 Bayer matrix. Stable between frames.
+    Ordered,
+    /// Error diffusion. Smoother, but crawls on moving pictures.
+    FloydSteinberg,
+}
+
+const BAYER: [[i16; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+fn add(c: Argb, [dr, dg, db]: [i16; 3]) -> Argb {
+    let [_, r, g, b] = c.decompose();
+    let ch = |v: u8, d: i16| (v as i16 + d).clamp(0, 255) as u8;
+    Argb::compose([0xFF, ch(r, dr), ch(g, dg), ch(b, db)])
+}
+
+impl Dither {
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "none" => Self::None,
+            "ordered" => Self::Ordered,
+            "floyd-steinberg" | "fs" => Self::FloydSteinberg,
+            _ => return None,
+        })
+    }
+
+    /// Snaps every pixel of `pixels`, rows of `width`, to the palette
+    /// of `depth`. Does nothing without a palette.
+    pub fn apply(self, depth: ColorDepth, pixels: &mut [Argb], width: usize) {
+        if matches!(depth, ColorDepth::Mono | ColorDepth::TrueColor) || width == 0 {
+            return;
+        }
+
+        // About the distance between neighbouring palette colors.
+        let spread = match depth {
+            ColorDepth::Ansi16 => 128,
+            _ => 40,
+        };
+
+        match self {
+            Self::None => {}
+
+            Self::Ordered => {
+                for (i, p) in pixels.iter_mut().enumerate() {
+                    let t = BAYER[i / width % 4][i % width % 4];
+                    let d = (t * 2 - 15) * spread / 32;
+                    *p = depth.snap(add(*p, [d; 3]));
+                }
+            }
+
+            Self::FloydSteinberg => {
+                let mut errors = vec![[0i16; 3]; width * 2 + 2];
+                let (mut this, mut next) = errors.split_at_mut(width + 1);
+
+                for row in pixels.chunks_exact_mut(width) {
+                    next.fill([0; 3]);
+
+                    for (x, p) in row.iter_mut().enumerate() {
+                        let old = add(*p, this[x]);
+                        let new = depth.snap(old);
+                        *p = new;
+
+                        let [_, r1, g1, b1] = old.decompose();
+                        let [_, r2, g2, b2] = new.decompose();
+                        let e = [
+                            r1 as i16 - r2 as i16,
+                            g1 as i16 - g2 as i16,
+                            b1 as i16 - b2 as i16,
+                        ];
+
+                        for c in 0..3 {
+                            this[x + 1][c] += e[c] * 7 / 16;
+                            if x > 0 {
+                                next[x - 1][c] += e[c] * 3 / 16;
+                            }
+                            next[x][c] += e[c] * 5 / 16;
+                            next[x + 1][c] += e[c] / 16;
+                        }
+                    }
+
+                    std::mem::swap(&mut this, &mut next);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use crate::graphics::{Argb, P2, Painter, Pixel};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PostEffect {
+    /// Box blur with the given radius.
+    Blur(u8),
+
+    /// Gaussian blur with the given standard deviation.
+    Gaussian(f32),
+
+    /// Blurs the bright parts and adds them back with the given strength.
+    Bloom(f32),
+
+    /// Contrast and saturation, 1.0 leaves the image as is.
+    Grade { contrast: f32, saturation: f32 },
+
+    /// Splits the red and blue channels apart toward the edges,
+    /// by up to the given number of pixels.
+    Aberration(f32),
+
+    /// Darkens the corners, 0.0 to 1.0.
+    Vignette(f32),
+
+    /// Darkens every other row by the given amount.
+    Scanlines(u8),
+
+    /// Averages blocks of the given size.
+    Pixelate(u8),
+
+    /// Keeps the previous output around, faded by the given amount.
+    Feedback(u8),
+}
+
+/// Luma above which pixels contribute to bloom.
+const BLOOM_THRESHOLD: u16 = 160;
+
+impl PostEffect {
+    /// Parses `name` or `name=value`. Grade takes either
+    /// `grade=saturation` or `grade=contrast:saturation`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (name, value) = match s.split_once('=') {
+            Some((n, v)) => (n, Some(v)),
+            None => (s, None),
+        };
+
+        let number = |s: &str| s.parse::<f32>().ok();
+        let v = |default: f32| value.map_or(Some(default), number);
+
+        Some(match name {
+            "blur" => Self::Blur(v(1.0)? as u8),
+            "gaussian" => Self::Gaussian(v(1.0)?),
+            "bloom" => Self::Bloom(v(0.8)?),
+            "grade" => {
+                let (contrast, saturation) = match value.and_then(|v| v.split_once(':')) {
+                    Some((c, s)) => (number(c)?, number(s)?),
+                    None => (1.2, v(1.3)?),
+                };
+
+                Self::Grade {
+                    contrast,
+                    saturation,
+                }
+            }
+            "grayscale" => Self::Grade {
+                contrast: 1.0,
+                saturation: 0.0,
+            },
+            "aberration" => Self::Aberration(v(1.5)?),
+            "vignette" => Self::Vignette(v(0.6)?),
+            "scanlines" => Self::Scanlines(v(96.0)? as u8),
+            "pixelate" => Self::Pixelate(v(2.0)? as u8),
+            "feedback" => Self::Feedback(v(200.0)? as u8),
+            _ => return None,
+        })
+    }
+}
+
+fn luma(c: Argb) -> u16 {
+    let [_, r, g, b] = c.decompose();
+    (r as u16 * 77 + g as u16 * 150 + b as u16 * 29) >> 8
+}
+
+fn channels(c: Argb) -> [f32; 3] {
+    let [_, r, g, b] = c.decompose();
+    [r as f32, g as f32, b as f32]
+}
+
+fn from_channels(a: u8, c: [f32; 3]) -> Argb {
+    let q = |x: f32| x.clamp(0.0, 255.0) as u8;
+    Argb::compose([a, q(c[0]), q(c[1]), q(c[2])])
+}
+
+/// A logical resolution copy of the canvas that the effects work on.
+struct Frame {
+    width: usize,
+    height: usize,
+    data: Vec<Argb>,
+}
+
+impl Frame {
+    fn get(&self, x: i32, y: i32) -> Argb {
+        let x = x.clamp(0, self.width as i32 - 1) as usize;
+        let y = y.clamp(0, self.height as i32 - 1) as usize;
+        self.data[y * self.width + x]
+    }
+
+    /// Separable convolution with a symmetric kernel,
+    /// `kernel[0]` being the center weight.
+    fn convolve(&mut self, kernel: &[f32]) {
+        let (w, h) = (self.width as i32, self.height as i32);
+        let r = kernel.len() as i32 - 1;
+
+        for horizontal in [true, false] {
+            let src = Frame {
+                data: self.data.clone(),
+                ..*self
+            };
+
+            for y in 0..h {
+                for x in 0..w {
+                    let mut acc = [0.0f32; 3];
+
+                    for k in -r..=r {
+                        let weight = kernel[k.unsigned_abs() as usize];
+
+                        let c = if horizontal {
+                            src.get(x + k, y)
+                        } else {
+                            src.get(x, y + k)
+                        };
+
+                        let c = channels(c);
+                        acc.iter_mut().zip(c).for_each(|(a, c)| *a += c * weight);
+                    }
+
+                    let i = (y * w + x) as usize;
+                    let [a, ..] = self.data[i].decompose();
+                    self.data[i] = from_channels(a, acc);
+                }
+            }
+        }
+    }
+
+    fn box_blur(&mut self, radius: u8) {
+        let n = radius as usize + 1;
+        let weight = 1.0 / (2 * radius as usize + 1) as f32;
+        self.convolve(&vec![weight; n]);
+    }
+
+    fn gaussian(&mut self, sigma: f32) {
+        let sigma = sigma.max(0.1);
+        let r = (sigma * 3.0).ceil() as usize;
+
+        let mut kernel: Vec<f32> = (0..=r)
+            .map(|i| (-((i * i) as f32) / (2.0 * sigma * sigma)).exp())
+            .collect();
+
+        let sum = kernel[0] + 2.0 * kernel[1..].iter().sum::<f32>();
+        kernel.iter_mut().for_each(|k| *k /= sum);
+
+        self.convolve(&kernel);
+    }
+
+    fn bloom(&mut self, strength: f32) {
+        let mut bright = Frame {
+            data: self
+                .data
+                .iter()
+                .map(|&c| if luma(c) > BLOOM_THRESHOLD { c } else { 0 })
+                .collect(),
+            ..*self
+        };
+
+        bright.gaussian(self.width.min(self.height) as f32 / 40.0 + 1.0);
+
+        for (p, b) in self.data.iter_mut().zip(bright.data) {
+            let [a, ..] = p.decompose();
+            let c = channels(*p);
+            let b = channels(b);
+            *p = from_channels(a, std::array::from_fn(|i| c[i] + b[i] * strength));
+        }
+    }
+
+    fn grade(&mut self, contrast: f32, saturation: f32) {
+        for p in self.data.iter_mut() {
+            let [a, ..] = p.decompose();
+            let c = channels(*p);
+            let l = luma(*p) as f32;
+
+            *p = from_channels(
+                a,
+                c.map(|c| {
+                    let c = l + (c - l) * saturation;
+                    (c - 128.0) * contrast + 128.0
+                }),
+            );
+        }
+    }
+
+    fn aberration(&mut self, amount: f32) {
+        let src = Frame {
+            data: self.data.clone(),
+            ..*self
+        };
+
+        let center = P2(self.width as i32, self.height as i32).to_cplx() * 0.5;
+        let norm = amount / center.0.max(center.1).max(1.0);
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let d = (P2(x as i32, y as i32).to_cplx() - center) * norm;
+                let (dx, dy) = (d.0.round() as i32, d.1.round() as i32);
+                let (x, y) = (x as i32, y as i32);
+
+                let [a, _, g, _] = src.get(x, y).decompose();
+                let [_, r, ..] = src.get(x + dx, y + dy).decompose();
+                let [.., b] = src.get(x - dx, y - dy).decompose();
+
+                self.data[y as usize * self.width + x as usize] = Argb::compose([a, r, g, b]);
+            }
+        }
+    }
+
+    fn vignette(&mut self, strength: f32) {
+        let center = P2(self.width as i32, self.height as i32).to_cplx() * 0.5;
+        let max = center.mag().max(1.0);
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let d = (P2(x as i32, y as i32).to_cplx() - center).mag() / max;
+                let f = 1.0 - strength * d * d;
+
+                let p = &mut self.data[y * self.width + x];
+                let [a, ..] = p.decompose();
+                *p = from_channels(a, channels(*p).map(|c| c * f));
+            }
+        }
+    }
+
+    fn scanlines(&mut self, darkness: u8) {
+        let f = 1.0 - darkness as f32 / 255.0;
+
+        for row in self.data.chunks_exact_mut(self.width).skip(1).step_by(2) {
+            for p in row {
+                let [a, ..] = p.decompose();
+                *p = from_channels(a, channels(*p).map(|c| c * f));
+            }
+        }
+    }
+
+    fn pixelate(&mut self, size: u8) {
+        let size = size.max(1) as usize;
+
+        for by in (0..self.height).step_by(size) {
+            for bx in (0..self.width).step_by(size) {
+                let ys = by..(by + size).min(self.height);
+                let xs = bx..(bx + size).min(self.width);
+                let n = (ys.len() * xs.len()) as f32;
+
+                let mut acc = [0.0f32; 3];
+
+                for y in ys.clone() {
+                    for x in xs.clone() {
+                        let c = channels(self.data[y * self.width + x]);
+                        acc.iter_mut().zip(c).for_each(|(a, c)| *a += c);
+                    }
+                }
+
+                let [a, ..] = self.data[by * self.width + bx].decompose();
+                let c = from_channels(a, acc.map(|c| c / n));
+
+                for y in ys.clone() {
+                    self.data[y * self.width + xs.start..y * self.width + xs.end].fill(c);
+                }
+            }
+        }
+    }
+
+    fn feedback(&mut self, previous: &[Argb], amount: u8) {
+        if previous.len() != self.data.len() {
+            return;
+        }
+
+        let f = amount as f32 / 255.0;
+
+        for (p, &q) in self.data.iter_mut().zip(previous) {
+            let [a, ..] = p.decompose();
+            let c = channels(*p);
+            let q = channels(q);
+            *p = from_channels(a, std::array::from_fn(|i| c[i].max(q[i] * f)));
+        }
+    }
+}
+
+/// Runs effects on the composited frame.
+///
+/// The frame is composited from the layers again every time, so
+/// effects never feed back into a visualizer's persistent canvas.
+#[derive(Default)]
+pub struct PostFx {
+    previous: Vec<Argb>,
+}
+
+impl PostFx {
+    pub fn apply(&mut self, pix: &mut Painter, effects: &[PostEffect]) {
+        if effects.is_empty() {
+            self.previous.clear();
+            return;
+        }
+
+        let (width, height) = pix.logical_sizeu();
+
+        let mut frame = Frame {
+            width,
+            height,
+            data: Vec::with_capacity(width * height),
+        };
+
+        pix.read_logical(&mut frame.data);
+
+        for &effect in effects {
+            match effect {
+                PostEffect::Blur(r) => frame.box_blur(r),
+                PostEffect::Gaussian(sigma) => frame.gaussian(sigma),
+                PostEffect::Bloom(strength) => frame.bloom(strength),
+                PostEffect::Grade {
+                    contrast,
+                    saturation,
+                } => frame.grade(contrast, saturation),
+                PostEffect::Aberration(amount) => frame.aberration(amount),
+                PostEffect::Vignette(strength) => frame.vignette(strength),
+                PostEffect::Scanlines(darkness) => frame.scanlines(darkness),
+                PostEffect::Pixelate(size) => frame.pixelate(size),
+                PostEffect::Feedback(amount) => frame.feedback(&self.previous, amount),
+            }
+        }
+
+        self.previous.clear();
+        self.previous.extend_from_slice(&frame.data);
+
+        pix.write_logical(&frame.data);
+    }
+}
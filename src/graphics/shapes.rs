@@ -0,0 +1,199 @@
+use std::f32::consts::TAU;
+
+use crate::graphics::{P2, Painter};
+use crate::math::Cplx;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FillRule {
+    #[allow(dead_code)]
+    EvenOdd,
+    NonZero,
+}
+
+fn arc_points(center: Cplx, radius: Cplx, start: f32, end: f32) -> Vec<Cplx> {
+    let sweep = (end - start).clamp(-TAU, TAU);
+    let n = ((radius.0.max(radius.1) * sweep.abs()) as usize).clamp(4, 512);
+
+    (0..=n)
+        .map(|i| {
+            let a = start + sweep * i as f32 / n as f32;
+            let (sin, cos) = a.sin_cos();
+            center + Cplx(cos * radius.0, sin * radius.1)
+        })
+        .collect()
+}
+
+// Everything here is filled in horizontal spans through
+// `rect_xy`, so the mixer, gradients and the interlaced
+// field logic all apply as usual.
+impl<'a> Painter<'a> {
+    /// Fills logical pixels `x0..x1` of row `y`.
+    fn span(&mut self, y: i32, x0: i32, x1: i32) {
+        let P2(w, h) = self.logical_size();
+
+        if y < 0 || y >= h {
+            return;
+        }
+
+        let x0 = x0.clamp(0, w);
+        let x1 = x1.clamp(0, w);
+
+        if x0 < x1 {
+            self.rect_xy(P2(x0, y), P2(x1, y));
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn triangle(&mut self, a: Cplx, b: Cplx, c: Cplx) {
+        self.polygon(&[a, b, c], FillRule::NonZero);
+    }
+
+    /// Scanline fill of any simple or self-intersecting polygon.
+    /// Pixel centers are at integer coordinates.
+    pub fn polygon(&mut self, points: &[Cplx], rule: FillRule) {
+        if points.len() < 3 {
+            return;
+        }
+
+        let (top, bottom) = points
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(t, b), p| (t.min(p.1), b.max(p.1)));
+
+        let h = self.logical_height() as i32;
+        let y0 = (top.ceil() as i32).max(0);
+        let y1 = (bottom.ceil() as i32).min(h);
+
+        let mut crossings: Vec<(f32, i32)> = Vec::with_capacity(points.len());
+
+        for y in y0..y1 {
+            let yf = y as f32;
+            crossings.clear();
+
+            for i in 0..points.len() {
+                let a = points[i];
+                let b = points[(i + 1) % points.len()];
+
+                if a.1 == b.1 {
+                    continue;
+                }
+
+                let (lo, hi, dir) = if a.1 < b.1 { (a, b, 1) } else { (b, a, -1) };
+
+                if yf < lo.1 || yf >= hi.1 {
+                    continue;
+                }
+
+                let x = lo.0 + (yf - lo.1) * (hi.0 - lo.0) / (hi.1 - lo.1);
+                crossings.push((x, dir));
+            }
+
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut winding = 0;
+
+            for pair in crossings.windows(2) {
+                let [(xl, dir), (xr, _)] = [pair[0], pair[1]];
+
+                winding += dir;
+
+                let inside = match rule {
+                    FillRule::NonZero => winding != 0,
+                    FillRule::EvenOdd => winding % 2 != 0,
+                };
+
+                if inside {
+                    self.span(y, xl.ceil() as i32, xr.ceil() as i32);
+                }
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn ellipse(&mut self, center: Cplx, radius: Cplx, filled: bool) {
+        let (rx, ry) = (radius.0.abs(), radius.1.abs());
+
+        if filled {
+            let y0 = (center.1 - ry).ceil() as i32;
+            let y1 = (center.1 + ry).floor() as i32;
+
+            for y in y0..=y1 {
+                let dy = (y as f32 - center.1) / ry.max(f32::EPSILON);
+                let dx = rx * (1.0 - dy * dy).max(0.0).sqrt();
+
+                self.span(
+                    y,
+                    (center.0 - dx).ceil() as i32,
+                    (center.0 + dx).floor() as i32 + 1,
+                );
+            }
+
+            return;
+        }
+
+        // Midpoint ellipse, in two regions split where
+        // the slope of the curve passes -1.
+        let c = center.to_p2();
+        let (a, b) = (rx.round(), ry.round());
+        let (a2, b2) = (a * a, b * b);
+
+        let mut plot4 = |x: f32, y: f32| {
+            let (x, y) = (x as i32, y as i32);
+            self.plot(P2(c.0 + x, c.1 + y));
+            self.plot(P2(c.0 - x, c.1 + y));
+            self.plot(P2(c.0 + x, c.1 - y));
+            self.plot(P2(c.0 - x, c.1 - y));
+        };
+
+        let (mut x, mut y) = (0.0, b);
+        let mut dx = 0.0;
+        let mut dy = 2.0 * a2 * y;
+        let mut d = b2 - a2 * b + a2 * 0.25;
+
+        while dx < dy {
+            plot4(x, y);
+
+            x += 1.0;
+            dx += 2.0 * b2;
+
+            if d < 0.0 {
+                d += dx + b2;
+            } else {
+                y -= 1.0;
+                dy -= 2.0 * a2;
+                d += dx - dy + b2;
+            }
+        }
+
+        let mut d = b2 * (x + 0.5) * (x + 0.5) + a2 * (y - 1.0) * (y - 1.0) - a2 * b2;
+
+        while y >= 0.0 {
+            plot4(x, y);
+
+            y -= 1.0;
+            dy -= 2.0 * a2;
+
+            if d > 0.0 {
+                d += a2 - dy;
+            } else {
+                x += 1.0;
+                dx += 2.0 * b2;
+                d += dx - dy + a2;
+            }
+        }
+    }
+
+    /// Outline of a circular arc from `start` to `end` (radians,
+    /// clockwise from the positive x axis) with the current `Stroke`.
+    #[allow(dead_code)]
+    pub fn arc(&mut self, center: Cplx, radius: f32, start: f32, end: f32) {
+        let points = arc_points(center, Cplx(radius, radius), start, end);
+        self.polyline(&points);
+    }
+
+    /// Filled slice of a circle between `start` and `end`.
+    pub fn pie(&mut self, center: Cplx, radius: f32, start: f32, end: f32) {
+        let mut points = arc_points(center, Cplx(radius, radius), start, end);
+        points.push(center);
+        self.polygon(&points, FillRule::NonZero);
+    }
+}
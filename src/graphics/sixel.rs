@@ -0,0 +1,105 @@
+use std::fmt::Write;
+
+use crate::graphics::{Argb, Pixel, image::Image};
+
+/// Levels of the 6x6x6 color cube every frame is mapped to. A fixed
+/// palette is cheap and does not flicker between frames.
+const LEVELS: usize = 6;
+const COLORS: usize = LEVELS * LEVELS * LEVELS;
+
+fn cube_index(c: Argb) -> usize {
+    let [_, r, g, b] = c.decompose();
+    let l = |v: u8| (v as usize * (LEVELS - 1) + 127) / 255;
+    l(r) * LEVELS * LEVELS + l(g) * LEVELS + l(b)
+}
+
+/// Appends `count` copies of the sixel `ch`, run length encoded.
+fn push_run(out: &mut String, ch: u8, count: usize) {
+    match count {
+        0 => {}
+        1..=3 => (0..count).for_each(|_| out.push(ch as char)),
+        _ => {
+            let _ = write!(out, "!{count}{}", ch as char);
+        }
+    }
+}
+
+/// Encodes `image` as a DEC sixel sequence.
+pub fn encode(image: &Image) -> String {
+    let (w, h) = (image.width(), image.height());
+
+    let mut out = String::with_capacity(w * h / 2);
+
+    // Square pixels, background left alone.
+    let _ = write!(out, "\x1bP0;1;0q\"1;1;{w};{h}");
+
+    for i in 0..COLORS {
+        let (r, g, b) = (i / (LEVELS * LEVELS), i / LEVELS % LEVELS, i % LEVELS);
+        let p = |v: usize| v * 100 / (LEVELS - 1);
+        let _ = write!(out, "#{i};2;{};{};{}", p(r), p(g), p(b));
+    }
+
+    let indices: Vec<u8> = (0..w * h)
+        .map(|i| cube_index(image.pixel(i % w, i / w)) as u8)
+        .collect();
+
+    let mut used = [false; COLORS];
+    let mut row = vec![0u8; w];
+
+    for band in (0..h).step_by(6) {
+        let rows = (h - band).min(6);
+
+        used.fill(false);
+        for y in band..band + rows {
+            for &c in &indices[y * w..(y + 1) * w] {
+                used[c as usize] = true;
+            }
+        }
+
+        let mut first = true;
+
+        for color in (0..COLORS).filter(|&c| used[c]) {
+            row.fill(0);
+
+            for dy in 0..rows {
+                let line = &indices[(band + dy) * w..(band + dy + 1) * w];
+
+                for (bits, &c) in row.iter_mut().zip(line) {
+                    if c as usize == color {
+                        *bits |= 1 << dy;
+                    }
+                }
+            }
+
+            if !first {
+                out.push('$');
+            }
+            first = false;
+
+            let _ = write!(out, "#{color}");
+
+            let mut run = (0, 0);
+
+            for &bits in &row {
+                let ch = 63 + bits;
+
+                if ch == run.0 {
+                    run.1 += 1;
+                } else {
+                    push_run(&mut out, run.0, run.1);
+                    run = (ch, 1);
+                }
+            }
+
+            // Empty sixels at the end of a line do not need to be sent.
+            if run.0 != 63 {
+                push_run(&mut out, run.0, run.1);
+            }
+        }
+
+        out.push('-');
+    }
+
+    out.push_str("\x1b\\");
+    out
+}
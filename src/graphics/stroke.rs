@@ -0,0 +1,394 @@
+use crate::graphics::{P2, Painter};
+use crate::math::Cplx;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineCap {
+    Butt,
+    Square,
+    Round,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineJoin {
+    Miter,
+    #[allow(dead_code)]
+    Bevel,
+    Round,
+}
+
+/// Miters longer than this many half widths become bevels.
+const MITER_LIMIT: f32 = 4.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Stroke {
+    pub width: f32,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    pub antialias: bool,
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            antialias: true,
+        }
+    }
+}
+
+impl Stroke {
+    pub fn new(width: f32) -> Self {
+        Self {
+            width,
+            ..Self::default()
+        }
+    }
+
+    pub fn cap(self, cap: LineCap) -> Self {
+        Self { cap, ..self }
+    }
+
+    pub fn join(self, join: LineJoin) -> Self {
+        Self { join, ..self }
+    }
+
+    #[allow(dead_code)]
+    pub fn aliased(self) -> Self {
+        Self {
+            antialias: false,
+            ..self
+        }
+    }
+}
+
+fn dot(a: Cplx, b: Cplx) -> f32 {
+    a.0 * b.0 + a.1 * b.1
+}
+
+fn cross(a: Cplx, b: Cplx) -> f32 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+/// Signed distance from `q` to a segment of half width `hw`.
+fn segment_sdf(q: Cplx, a: Cplx, b: Cplx, hw: f32, cap: LineCap) -> f32 {
+    let d = b - a;
+    let len = d.mag();
+
+    if len < f32::EPSILON {
+        return match cap {
+            LineCap::Round => (q - a).mag() - hw,
+            LineCap::Square => {
+                let r = (q - a).abs();
+                r.0.max(r.1) - hw
+            }
+            LineCap::Butt => f32::MAX,
+        };
+    }
+
+    let u = d / len;
+    let along = dot(q - a, u);
+    let perp = cross(u, q - a).abs();
+
+    match cap {
+        LineCap::Round => {
+            let t = along.clamp(0.0, len);
+            (q - (a + u * t)).mag() - hw
+        }
+
+        LineCap::Butt | LineCap::Square => {
+            let ext = if cap == LineCap::Square { hw } else { 0.0 };
+            let ax = (along - len * 0.5).abs() - (len * 0.5 + ext);
+            let ay = perp - hw;
+            ax.max(ay)
+        }
+    }
+}
+
+/// Approximate signed distance to a convex polygon,
+/// exact on the inside and along the edges.
+fn convex_sdf(q: Cplx, poly: &[Cplx]) -> f32 {
+    let n = poly.len() as f32;
+    let centroid = poly.iter().fold(Cplx::zero(), |a, &p| a + p) / n;
+
+    let mut dist = f32::MIN;
+
+    for i in 0..poly.len() {
+        let a = poly[i];
+        let b = poly[(i + 1) % poly.len()];
+
+        let e = b - a;
+        let len = e.mag();
+
+        if len < f32::EPSILON {
+            continue;
+        }
+
+        let mut normal = Cplx(e.1, -e.0) / len;
+
+        if dot(centroid - a, normal) > 0.0 {
+            normal = -normal;
+        }
+
+        dist = dist.max(dot(q - a, normal));
+    }
+
+    dist
+}
+
+/// Per pixel coverage over a bounding box, so that overlapping
+/// segments and joins are only blended once.
+struct Coverage {
+    origin: P2,
+    width: usize,
+    height: usize,
+    data: Vec<f32>,
+    antialias: bool,
+}
+
+impl Coverage {
+    fn new(lo: Cplx, hi: Cplx, bound: P2, antialias: bool) -> Self {
+        let x0 = (lo.0.floor() as i32).clamp(0, bound.0);
+        let y0 = (lo.1.floor() as i32).clamp(0, bound.1);
+        let x1 = (hi.0.ceil() as i32 + 1).clamp(0, bound.0);
+        let y1 = (hi.1.ceil() as i32 + 1).clamp(0, bound.1);
+
+        let width = (x1 - x0) as usize;
+        let height = (y1 - y0) as usize;
+
+        Self {
+            origin: P2(x0, y0),
+            width,
+            height,
+            data: vec![0.0; width * height],
+            antialias,
+        }
+    }
+
+    fn add(&mut self, lo: Cplx, hi: Cplx, sdf: impl Fn(Cplx) -> f32) {
+        let P2(ox, oy) = self.origin;
+
+        let x0 = (lo.0.floor() as i32 - ox).clamp(0, self.width as i32) as usize;
+        let y0 = (lo.1.floor() as i32 - oy).clamp(0, self.height as i32) as usize;
+        let x1 = (hi.0.ceil() as i32 + 1 - ox).clamp(0, self.width as i32) as usize;
+        let y1 = (hi.1.ceil() as i32 + 1 - oy).clamp(0, self.height as i32) as usize;
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let q = Cplx((x as i32 + ox) as f32, (y as i32 + oy) as f32);
+                let d = sdf(q);
+
+                let c = if self.antialias {
+                    (0.5 - d).clamp(0.0, 1.0)
+                } else if d <= 0.0 {
+                    1.0
+                } else {
+                    0.0
+                };
+
+                let cell = &mut self.data[y * self.width + x];
+                *cell = cell.max(c);
+            }
+        }
+    }
+}
+
+impl<'a> Painter<'a> {
+    pub fn stroke(&mut self, s: Stroke) {
+        self.stroke = s;
+    }
+
+    /// Xiaolin Wu's anti-aliased line. The coverage is
+    /// passed as alpha to the current mixer, so this
+    /// is best paired with `mixerm` or `u32::add`.
+    pub fn line_aa(&mut self, ps: Cplx, pe: Cplx) {
+        let steep = (pe.1 - ps.1).abs() > (pe.0 - ps.0).abs();
+
+        let (mut a, mut b) = if steep {
+            (Cplx(ps.1, ps.0), Cplx(pe.1, pe.0))
+        } else {
+            (ps, pe)
+        };
+
+        if a.0 > b.0 {
+            std::mem::swap(&mut a, &mut b);
+        }
+
+        let dx = b.0 - a.0;
+        let dy = b.1 - a.1;
+        let gradient = if dx.abs() < f32::EPSILON {
+            1.0
+        } else {
+            dy / dx
+        };
+
+        // Mixers under the fast feature ignore alpha,
+        // so uncovered pixels must not be plotted at all.
+        let mut plot = |x: i32, y: i32, c: f32| {
+            if c <= 0.0 {
+                return;
+            }
+
+            let p = if steep { P2(y, x) } else { P2(x, y) };
+            self.plot_alpha(p, (c.clamp(0.0, 1.0) * 255.0) as u8);
+        };
+
+        let mut endpoint = |p: Cplx, gap: f32| {
+            let xend = p.0.round();
+            let yend = p.1 + gradient * (xend - p.0);
+            let x = xend as i32;
+            let y = yend.floor() as i32;
+            let f = yend - yend.floor();
+
+            plot(x, y, (1.0 - f) * gap);
+            plot(x, y + 1, f * gap);
+
+            (x, yend)
+        };
+
+        let (x1, yend) = endpoint(a, 1.0 - (a.0 + 0.5).fract());
+        let (x2, _) = endpoint(b, (b.0 + 0.5).fract());
+
+        let mut intery = yend + gradient;
+
+        for x in x1 + 1..x2 {
+            let y = intery.floor();
+            let f = intery - y;
+
+            plot(x, y as i32, 1.0 - f);
+            plot(x, y as i32 + 1, f);
+
+            intery += gradient;
+        }
+    }
+
+    /// A line with the width and cap of the current `Stroke`.
+    #[allow(dead_code)]
+    pub fn line_thick(&mut self, ps: Cplx, pe: Cplx) {
+        self.polyline(&[ps, pe]);
+    }
+
+    /// Connected lines through `points` using the width,
+    /// caps and joins of the current `Stroke`.
+    pub fn polyline(&mut self, points: &[Cplx]) {
+        let Some(&first) = points.first() else {
+            return;
+        };
+
+        let Stroke {
+            width,
+            cap,
+            join,
+            antialias,
+        } = self.stroke;
+
+        let hw = width * 0.5;
+        let pad = Cplx(hw + 1.0, hw + 1.0) * MITER_LIMIT;
+
+        let (lo, hi) = points.iter().fold((first, first), |(lo, hi), p| {
+            (
+                Cplx(lo.0.min(p.0), lo.1.min(p.1)),
+                Cplx(hi.0.max(p.0), hi.1.max(p.1)),
+            )
+        });
+
+        let mut cov = Coverage::new(lo - pad, hi + pad, self.logical_size(), antialias);
+
+        if points.len() == 1 {
+            let pad = Cplx(hw + 1.0, hw + 1.0);
+            cov.add(first - pad, first + pad, |q| {
+                segment_sdf(q, first, first, hw, cap)
+            });
+        }
+
+        let last = points.len().saturating_sub(2);
+
+        for (i, seg) in points.windows(2).enumerate() {
+            let [a, b] = [seg[0], seg[1]];
+
+            // Ends of the polyline get the caps, joints are
+            // butted and then patched up by the joins below.
+            let cap_a = if i == 0 { cap } else { LineCap::Butt };
+            let cap_b = if i == last { cap } else { LineCap::Butt };
+
+            let pad = Cplx(hw + 1.0, hw + 1.0);
+            let slo = Cplx(a.0.min(b.0), a.1.min(b.1)) - pad;
+            let shi = Cplx(a.0.max(b.0), a.1.max(b.1)) + pad;
+
+            cov.add(slo, shi, |q| {
+                let mid = (a + b).center();
+
+                if cap_a == cap_b {
+                    return segment_sdf(q, a, b, hw, cap_a);
+                }
+
+                // Different caps on both ends, split the
+                // segment in half and cap each piece.
+                segment_sdf(q, a, mid, hw, cap_a).min(segment_sdf(q, mid, b, hw, cap_b))
+            });
+        }
+
+        for w in points.windows(3) {
+            let [a, v, b] = [w[0], w[1], w[2]];
+
+            let d1 = v - a;
+            let d2 = b - v;
+
+            if d1.mag() < f32::EPSILON || d2.mag() < f32::EPSILON {
+                continue;
+            }
+
+            let turn = cross(d1, d2);
+
+            // Normals pointing to the outer side of the turn.
+            let side = if turn > 0.0 { -1.0 } else { 1.0 };
+            let n1 = d1.normalize().times_i() * (side * hw);
+            let n2 = d2.normalize().times_i() * (side * hw);
+
+            let pad = Cplx(hw, hw) * MITER_LIMIT + Cplx(1.0, 1.0);
+
+            match join {
+                LineJoin::Round => {
+                    cov.add(v - pad, v + pad, |q| (q - v).mag() - hw);
+                }
+
+                LineJoin::Bevel => {
+                    let tri = [v, v + n1, v + n2];
+                    cov.add(v - pad, v + pad, |q| convex_sdf(q, &tri));
+                }
+
+                LineJoin::Miter => {
+                    let m = (n1 + n2).normalize();
+                    let cos = dot(m, n1.normalize());
+                    let len = if cos > f32::EPSILON {
+                        hw / cos
+                    } else {
+                        f32::MAX
+                    };
+
+                    if len > hw * MITER_LIMIT {
+                        let tri = [v, v + n1, v + n2];
+                        cov.add(v - pad, v + pad, |q| convex_sdf(q, &tri));
+                    } else {
+                        let quad = [v, v + n1, v + m * len, v + n2];
+                        cov.add(v - pad, v + pad, |q| convex_sdf(q, &quad));
+                    }
+                }
+            }
+        }
+
+        let P2(ox, oy) = cov.origin;
+
+        for y in 0..cov.height {
+            for x in 0..cov.width {
+                let c = cov.data[y * cov.width + x];
+
+                if c > 0.0 {
+                    let p = P2(ox + x as i32, oy + y as i32);
+                    self.plot_alpha(p, (c * 255.0) as u8);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use crate::data::DEFAULT_BG_COLOR;
+use crate::graphics::{Argb, Painter, Pixel, gradient::Gradient, gradient::interpolate};
+
+pub const RAMP_SIZE: usize = 4;
+
+pub struct Palette {
+    pub foreground: Argb,
+
+    /// For grids, crosses and other things that should stay in the back.
+    pub muted: Argb,
+
+    pub accents: [Argb; 2],
+
+    /// Evenly spaced stops, from quiet to loud.
+    pub ramp: [Argb; RAMP_SIZE],
+}
+
+/// A named set of colors for the visualizers.
+///
+/// Visualizers pass their own ("native") color to every query, which
+/// is returned untouched by the classic theme. Other themes replace it
+/// with a color from their palette but keep the alpha of the native one,
+/// since some visualizers use it as a blending factor.
+pub struct Theme {
+    pub name: &'static str,
+    pub background: Argb,
+    pub palette: Option<Palette>,
+}
+
+pub const THEMES: &[Theme] = &[
+    Theme {
+        name: "classic",
+        background: DEFAULT_BG_COLOR,
+        palette: None,
+    },
+    Theme {
+        name: "nord",
+        background: 0xFF_2E_34_40,
+        palette: Some(Palette {
+            foreground: 0xFF_EC_EF_F4,
+            muted: 0xFF_4C_56_6A,
+            accents: [0xFF_88_C0_D0, 0xFF_BF_61_6A],
+            ramp: [0xFF_5E_81_AC, 0xFF_81_A1_C1, 0xFF_88_C0_D0, 0xFF_8F_BC_BB],
+        }),
+    },
+    Theme {
+        name: "gruvbox",
+        background: 0xFF_28_28_28,
+        palette: Some(Palette {
+            foreground: 0xFF_EB_DB_B2,
+            muted: 0xFF_50_49_45,
+            accents: [0xFF_FA_BD_2F, 0xFF_FB_49_34],
+            ramp: [0xFF_83_A5_98, 0xFF_8E_C0_7C, 0xFF_B8_BB_26, 0xFF_FA_BD_2F],
+        }),
+    },
+    Theme {
+        name: "dracula",
+        background: 0xFF_28_2A_36,
+        palette: Some(Palette {
+            foreground: 0xFF_F8_F8_F2,
+            muted: 0xFF_44_47_5A,
+            accents: [0xFF_BD_93_F9, 0xFF_FF_79_C6],
+            ramp: [0xFF_62_72_A4, 0xFF_BD_93_F9, 0xFF_FF_79_C6, 0xFF_FF_B8_6C],
+        }),
+    },
+    Theme {
+        name: "solarized",
+        background: 0xFF_00_2B_36,
+        palette: Some(Palette {
+            foreground: 0xFF_EE_E8_D5,
+            muted: 0xFF_07_36_42,
+            accents: [0xFF_26_8B_D2, 0xFF_DC_32_2F],
+            ramp: [0xFF_26_8B_D2, 0xFF_2A_A1_98, 0xFF_85_99_00, 0xFF_B5_89_00],
+        }),
+    },
+    Theme {
+        name: "catppuccin",
+        background: 0xFF_1E_1E_2E,
+        palette: Some(Palette {
+            foreground: 0xFF_CD_D6_F4,
+            muted: 0xFF_45_47_5A,
+            accents: [0xFF_CB_A6_F7, 0xFF_F3_8B_A8],
+            ramp: [0xFF_89_B4_FA, 0xFF_94_E2_D5, 0xFF_A6_E3_A1, 0xFF_F9_E2_AF],
+        }),
+    },
+    Theme {
+        name: "mono",
+        background: 0xFF_10_10_10,
+        palette: Some(Palette {
+            foreground: 0xFF_E0_E0_E0,
+            muted: 0xFF_30_30_30,
+            accents: [0xFF_FF_FF_FF, 0xFF_90_90_90],
+            ramp: [0xFF_50_50_50, 0xFF_80_80_80, 0xFF_B0_B0_B0, 0xFF_FF_FF_FF],
+        }),
+    },
+];
+
+fn keep_alpha(themed: Argb, native: Argb) -> Argb {
+    let [a, ..] = native.decompose();
+    themed.set_alpha(a)
+}
+
+impl Theme {
+    pub fn find(name: &str) -> Option<usize> {
+        THEMES
+            .iter()
+            .position(|t| t.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn foreground(&self, native: Argb) -> Argb {
+        match &self.palette {
+            Some(p) => keep_alpha(p.foreground, native),
+            None => native,
+        }
+    }
+
+    pub fn muted(&self, native: Argb) -> Argb {
+        match &self.palette {
+            Some(p) => keep_alpha(p.muted, native),
+            None => native,
+        }
+    }
+
+    /// Accent `i`, wrapping around the available ones.
+    pub fn accent(&self, i: usize, native: Argb) -> Argb {
+        match &self.palette {
+            Some(p) => keep_alpha(p.accents[i % p.accents.len()], native),
+            None => native,
+        }
+    }
+
+    /// Color at `t` (0..=1) along the ramp.
+    pub fn ramp(&self, t: f32, native: Argb) -> Argb {
+        let Some(p) = &self.palette else {
+            return native;
+        };
+
+        let t = t.clamp(0.0, 1.0) * (RAMP_SIZE - 1) as f32;
+        let i = (t as usize).min(RAMP_SIZE - 2);
+
+        keep_alpha(interpolate(p.ramp[i], p.ramp[i + 1], t - i as f32), native)
+    }
+
+    /// Adds the ramp as stops to `g`, or the `native` stops for the classic theme.
+    pub fn gradient(&self, g: Gradient, native: &[(f32, Argb)]) -> Gradient {
+        match &self.palette {
+            Some(p) => p
+                .ramp
+                .iter()
+                .enumerate()
+                .fold(g, |g, (i, &c)| g.stop(i as f32 / (RAMP_SIZE - 1) as f32, c)),
+
+            None => native.iter().fold(g, |g, &(t, c)| g.stop(t, c)),
+        }
+    }
+}
+
+impl<'a> Painter<'a> {
+    pub fn set_theme(&mut self, theme: &'static Theme) {
+        self.theme = theme;
+        self.background = theme.background;
+    }
+
+    pub fn theme(&self) -> &'static Theme {
+        self.theme
+    }
+}
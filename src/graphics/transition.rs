@@ -0,0 +1,129 @@
+use crate::graphics::{Argb, P2, Painter, Pixel, layer::Layer};
+use crate::math::rng::random_int;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Transition {
+    /// Switch instantly.
+    None,
+    Crossfade,
+    Wipe,
+    Slide,
+    Dissolve,
+    Zoom,
+    /// A different one every switch.
+    Random,
+}
+
+impl Transition {
+    const ANIMATED: [Self; 5] = [
+        Self::Crossfade,
+        Self::Wipe,
+        Self::Slide,
+        Self::Dissolve,
+        Self::Zoom,
+    ];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "none" => Self::None,
+            "crossfade" => Self::Crossfade,
+            "wipe" => Self::Wipe,
+            "slide" => Self::Slide,
+            "dissolve" => Self::Dissolve,
+            "zoom" => Self::Zoom,
+            "random" => Self::Random,
+            _ => return None,
+        })
+    }
+
+    /// Picks the transition for one switch.
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Random => Self::ANIMATED[random_int(Self::ANIMATED.len() as u32) as usize],
+            t => t,
+        }
+    }
+}
+
+/// Threshold of a logical pixel for `Dissolve`, spread evenly over 0..256.
+fn dither(x: usize, y: usize) -> u32 {
+    let h = (x as u32)
+        .wrapping_mul(0x9E37_79B1)
+        .wrapping_add((y as u32).wrapping_mul(0x85EB_CA77));
+    (h ^ (h >> 15)).wrapping_mul(0xC2B2_AE3D) >> 24
+}
+
+impl<'a> Painter<'a> {
+    /// Draws `from` turning into `to`, `t` of the way there, with
+    /// the top left corner at `origin` in physical pixels. Both
+    /// layers must have the same size.
+    pub fn transition(&mut self, kind: Transition, from: &Layer, to: &Layer, t: f32, origin: P2) {
+        let (ox, oy) = (origin.0.max(0) as usize, origin.1.max(0) as usize);
+
+        if ox >= self.width || oy >= self.height {
+            return;
+        }
+
+        let (lw, lh) = (to.width(), to.height());
+        let w = lw.min(self.width - ox);
+        let h = lh.min(self.height - oy);
+
+        // Eased so that both ends of the animation settle.
+        let t = t.clamp(0.0, 1.0);
+        let t = t * t * (3.0 - 2.0 * t);
+
+        let scale = self.scale as usize;
+        let alpha = (t * 255.0) as u8;
+        let shift = (t * lw as f32) as usize;
+
+        // The outgoing frame grows to twice its size while fading out.
+        let zoom = 1.0 / (1.0 + t);
+        let (cx, cy) = (lw as f32 / 2.0, lh as f32 / 2.0);
+
+        let src = |l: &Layer, x: usize, y: usize| l.as_slice()[y * l.width() + x];
+
+        for y in 0..h {
+            let row = (oy + y) * self.width + ox;
+
+            for x in 0..w {
+                let c: Argb = match kind {
+                    Transition::Wipe => {
+                        if x < shift {
+                            src(to, x, y)
+                        } else {
+                            src(from, x, y)
+                        }
+                    }
+
+                    // The incoming frame pushes the outgoing one out to the left.
+                    Transition::Slide => {
+                        if x + shift < lw {
+                            src(from, x + shift, y)
+                        } else {
+                            src(to, x + shift - lw, y)
+                        }
+                    }
+
+                    Transition::Dissolve => {
+                        if dither(x / scale, y / scale) < alpha as u32 {
+                            src(to, x, y)
+                        } else {
+                            src(from, x, y)
+                        }
+                    }
+
+                    Transition::Zoom => {
+                        let sx = (cx + (x as f32 - cx) * zoom) as usize;
+                        let sy = (cy + (y as f32 - cy) * zoom) as usize;
+                        src(from, sx.min(lw - 1), sy.min(lh - 1))
+                            .mix(src(to, x, y).set_alpha(alpha))
+                    }
+
+                    _ => src(from, x, y).mix(src(to, x, y).set_alpha(alpha)),
+                };
+
+                self.buffer[row + x] = c;
+            }
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use crate::graphics::{Argb, Pixel};
+
+/// Filters for blowing up a frame drawn at the logical size.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Upscale {
+    Nearest,
+    /// Bilinear, but only across the one pixel wide edge
+    /// between texels, so that they stay crisp.
+    Sharp,
+    /// Darkens the last row of every texel.
+    Scanline,
+    /// EPX, which rounds off diagonal edges of pixel art.
+    /// Scale 4 runs it twice, other scales than 2 and 3 fall
+    /// back to nearest.
+    Scale2x,
+}
+
+fn lerp(a: Argb, b: Argb, t: u8) -> Argb {
+    let [_, ar, ag, ab] = a.decompose();
+    let [_, br, bg, bb] = b.decompose();
+    let t = t as u16;
+
+    let ch = |a: u8, b: u8| ((a as u16 * (255 - t) + b as u16 * t + 127) / 255) as u8;
+
+    Argb::compose([0xFF, ch(ar, br), ch(ag, bg), ch(ab, bb)])
+}
+
+/// Weights of the sharp bilinear filter along one axis: the texel to
+/// the left (or above) of output pixel `i` and how far to go into the next.
+fn sharp_weights(len: usize, scale: usize) -> Vec<(usize, u8)> {
+    let s = scale as f32;
+
+    (0..len * scale)
+        .map(|i| {
+            let u = (i as f32 + 0.5) / s - 0.5;
+            let base = u.floor();
+            let f = ((u - base - 0.5) * s + 0.5).clamp(0.0, 1.0);
+
+            let base = (base.max(0.0) as usize).min(len - 1);
+            (base, (f * 255.0) as u8)
+        })
+        .collect()
+}
+
+/// One round of EPX from `src` to `dst`, which is `scale` (2 or 3)
+/// times as large along each side.
+fn epx(src: &[Argb], width: usize, height: usize, dst: &mut [Argb], scale: usize) {
+    let at = |x: isize, y: isize| {
+        let x = x.clamp(0, width as isize - 1) as usize;
+        let y = y.clamp(0, height as isize - 1) as usize;
+        src[y * width + x]
+    };
+
+    let dw = width * scale;
+
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            let [a, b, c] = [at(x - 1, y - 1), at(x, y - 1), at(x + 1, y - 1)];
+            let [d, e, f] = [at(x - 1, y), at(x, y), at(x + 1, y)];
+            let [g, h, i] = [at(x - 1, y + 1), at(x, y + 1), at(x + 1, y + 1)];
+
+            let out: [Argb; 9] = if b != h && d != f {
+                if scale == 2 {
+                    [
+                        if d == b { d } else { e },
+                        if b == f { f } else { e },
+                        0,
+                        if d == h { d } else { e },
+                        if h == f { f } else { e },
+                        0,
+                        0,
+                        0,
+                        0,
+                    ]
+                } else {
+                    [
+                        if d == b { d } else { e },
+                        if (d == b && e != c) || (b == f && e != a) {
+                            b
+                        } else {
+                            e
+                        },
+                        if b == f { f } else { e },
+                        if (d == b && e != g) || (d == h && e != a) {
+                            d
+                        } else {
+                            e
+                        },
+                        e,
+                        if (b == f && e != i) || (h == f && e != c) {
+                            f
+                        } else {
+                            e
+                        },
+                        if d == h { d } else { e },
+                        if (d == h && e != i) || (h == f && e != g) {
+                            h
+                        } else {
+                            e
+                        },
+                        if h == f { f } else { e },
+                    ]
+                }
+            } else {
+                [e; 9]
+            };
+
+            let (x, y) = (x as usize, y as usize);
+
+            for sy in 0..scale {
+                let row = (y * scale + sy) * dw + x * scale;
+                dst[row..row + scale].copy_from_slice(&out[sy * 3..sy * 3 + scale]);
+            }
+        }
+    }
+}
+
+impl Upscale {
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "nearest" => Self::Nearest,
+            "sharp" => Self::Sharp,
+            "scanline" => Self::Scanline,
+            "scale2x" => Self::Scale2x,
+            _ => return None,
+        })
+    }
+
+    /// Blows `src` up by `scale` into the top left of `dst`, whose rows are
+    /// `dst_width` long. `dst` has to fit `width * scale` by `height * scale`.
+    pub fn upscale(
+        self,
+        src: &[Argb],
+        width: usize,
+        height: usize,
+        dst: &mut [Argb],
+        dst_width: usize,
+        scale: usize,
+    ) {
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let out_width = width * scale;
+        let mut rows = dst.chunks_exact_mut(dst_width).take(height * scale);
+
+        match self {
+            Self::Sharp if scale > 1 => {
+                let xs = sharp_weights(width, scale);
+                let ys = sharp_weights(height, scale);
+
+                for (row, &(y, ty)) in rows.zip(&ys) {
+                    let y1 = (y + 1).min(height - 1);
+                    let top = &src[y * width..][..width];
+                    let bottom = &src[y1 * width..][..width];
+
+                    for (d, &(x, tx)) in row.iter_mut().zip(&xs) {
+                        let x1 = (x + 1).min(width - 1);
+                        let t = lerp(top[x], top[x1], tx);
+                        let b = lerp(bottom[x], bottom[x1], tx);
+                        *d = lerp(t, b, ty);
+                    }
+                }
+            }
+
+            Self::Scale2x if matches!(scale, 2..=4) => {
+                let mut big = vec![0; out_width * height * scale];
+
+                if scale == 4 {
+                    let mut half = vec![0; width * height * 4];
+                    epx(src, width, height, &mut half, 2);
+                    epx(&half, width * 2, height * 2, &mut big, 2);
+                } else {
+                    epx(src, width, height, &mut big, scale);
+                }
+
+                for (row, line) in rows.zip(big.chunks_exact(out_width)) {
+                    row[..out_width].copy_from_slice(line);
+                }
+            }
+
+            _ => {
+                for y in 0..height {
+                    let line = &src[y * width..][..width];
+
+                    for sy in 0..scale {
+                        let Some(row) = rows.next() else {
+                            return;
+                        };
+
+                        let dark = self == Self::Scanline && scale > 1 && sy == scale - 1;
+
+                        for (chunk, &c) in row[..out_width].chunks_exact_mut(scale).zip(line) {
+                            chunk.fill(if dark { lerp(c, 0, 0x80) } else { c });
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -18,7 +18,22 @@ use crate::data::Program;
 fn main() {
     let args = env::args().collect::<Vec<String>>();
 
-    let prog = data::Program::new().eval_args(&mut args.iter());
+    if args.get(1).is_some_and(|a| a == "golden") {
+        modes::golden_mode::golden_main(&args[2..]);
+        return;
+    }
+
+    if args.get(1).is_some_and(|a| a == "raw") {
+        modes::raw_mode::raw_main(&args[2..]);
+        return;
+    }
+
+    if args.get(1).is_some_and(|a| a == "render") {
+        modes::render_mode::render_main(&args[2..]);
+        return;
+    }
+
+    let prog = data::Program::new().eval_args(&mut args.iter().skip(1));
 
     let stream = get_source();
     stream.play().unwrap();
@@ -2,11 +2,29 @@ use std::vec;
 
 use crate::math::fast::{bit_reverse, ilog2};
 
+#[cfg(feature = "simd")]
+use crate::math::simd;
+
 use super::Cplx;
 
 const MAX_POWER: usize = 13;
 const MAX_SIZE: usize = 1 << MAX_POWER;
 
+/// One radix-2 pass over `l` and `r` with the twiddles in `root`.
+fn butterfly(l: &mut [Cplx], r: &mut [Cplx], root: &[Cplx]) {
+    let halfsize = l.len();
+
+    for i in 0..halfsize {
+        r[i] *= root[i];
+    }
+
+    for i in 0..halfsize {
+        let z = r[i];
+        r[i] = l[i] - z;
+        l[i] += z;
+    }
+}
+
 pub struct Fft {
     twiddles: Vec<Cplx>,
     butterfly_swap_list: Vec<(usize, usize)>,
@@ -110,15 +128,13 @@ impl Fft {
             a.chunks_exact_mut(size).for_each(|chunk| {
                 let (l, r) = chunk.split_at_mut(halfsize);
 
-                for i in 0..halfsize {
-                    r[i] *= root[i];
+                #[cfg(feature = "simd")]
+                if halfsize >= simd::LANES {
+                    simd::butterfly(l, r, &root[..halfsize]);
+                    return;
                 }
 
-                for i in 0..halfsize {
-                    let z = r[i];
-                    r[i] = l[i] - z;
-                    l[i] += z;
-                }
+                butterfly(l, r, root);
             });
 
             halfsize *= 2;
@@ -151,3 +167,103 @@ impl Fft {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::hint::black_box;
+    use std::time::Instant;
+
+    fn signal(n: usize) -> Vec<Cplx> {
+        (0..n)
+            .map(|i| {
+                let t = i as f32 / n as f32;
+                Cplx(
+                    (t * 37.0).sin() + 0.5 * (t * 301.0).cos(),
+                    (t * 11.0).cos() - 0.25 * (t * 97.0).sin(),
+                )
+            })
+            .collect()
+    }
+
+    /// Straight from the definition, in f64.
+    fn dft(x: &[Cplx]) -> Vec<(f64, f64)> {
+        let n = x.len();
+
+        (0..n)
+            .map(|k| {
+                x.iter().enumerate().fold((0.0, 0.0), |(re, im), (j, c)| {
+                    let angle = -std::f64::consts::TAU * (j * k % n) as f64 / n as f64;
+                    let (sin, cos) = angle.sin_cos();
+                    let (a, b) = (c.0 as f64, c.1 as f64);
+                    (re + a * cos - b * sin, im + a * sin + b * cos)
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn exec_matches_dft() {
+        for n in [4, 8, 16, 64, 512, 2048] {
+            let mut x = signal(n);
+            let expected = dft(&x);
+
+            Fft::new(n).exec(&mut x);
+
+            let peak = expected.iter().fold(0.0f64, |m, c| m.max(c.0.hypot(c.1)));
+
+            for (k, (a, e)) in x.iter().zip(&expected).enumerate() {
+                let err = (a.0 as f64 - e.0).hypot(a.1 as f64 - e.1);
+                assert!(err <= peak * 1e-5, "n = {n}, bin {k}: {a:?} vs {e:?}");
+            }
+        }
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn simd_butterfly_matches_scalar() {
+        for halfsize in [8, 16, 256, 1024] {
+            let input = signal(halfsize * 2);
+            let root: Vec<Cplx> = (0..halfsize)
+                .map(|j| Cplx::euler(-std::f32::consts::PI * j as f32 / halfsize as f32))
+                .collect();
+
+            let mut scalar = input.clone();
+            let (l, r) = scalar.split_at_mut(halfsize);
+            butterfly(l, r, &root);
+
+            let mut lanes = input.clone();
+            let (l, r) = lanes.split_at_mut(halfsize);
+            simd::butterfly(l, r, &root);
+
+            for (a, b) in scalar.iter().zip(&lanes) {
+                assert!((a.0 - b.0).abs() <= 1e-6 && (a.1 - b.1).abs() <= 1e-6);
+            }
+        }
+    }
+
+    /// Timings, not a check. Compare
+    /// `cargo test --release fft_timing -- --ignored --nocapture`
+    /// with and without `--features simd`.
+    #[test]
+    #[ignore]
+    fn fft_timing() {
+        for n in [256, 1024, 4096, 8192] {
+            let fft = Fft::new(n);
+            let input = signal(n);
+            let mut x = input.clone();
+
+            let runs = (1 << 24) / n;
+            let start = Instant::now();
+
+            for _ in 0..runs {
+                x.copy_from_slice(&input);
+                fft.exec(black_box(&mut x));
+            }
+
+            let ns = start.elapsed().as_nanos() as f64 / runs as f64;
+            println!("fft {n:>5}: {ns:>10.0} ns");
+        }
+    }
+}
@@ -5,6 +5,8 @@ pub mod dct;
 pub mod fast;
 mod fft;
 pub mod rng;
+#[cfg(feature = "simd")]
+pub mod simd;
 
 use std::ops;
 
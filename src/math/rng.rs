@@ -18,27 +18,43 @@ impl FastU32 {
     }
 }
 
-pub fn time_seed() -> usize {
+const RANDOM_FLOAT_SEED: f32 = 0.2132454;
+
+thread_local! {
+    static FIXED_CLOCK: std::cell::Cell<Option<u64>> = const { std::cell::Cell::new(None) };
+    static VAR: std::cell::RefCell<f32> = const { std::cell::RefCell::new(RANDOM_FLOAT_SEED) };
+}
+
+/// Replaces the system clock used for seeding with a counter,
+/// so that offline renders come out the same every time.
+/// Also restarts `random_float`.
+pub fn fix_clock(seed: u64) {
+    FIXED_CLOCK.set(Some(seed));
+    VAR.set(RANDOM_FLOAT_SEED);
+}
+
+fn clock_nanos() -> u64 {
+    if let Some(t) = FIXED_CLOCK.get() {
+        let t = t.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        FIXED_CLOCK.set(Some(t));
+        return t;
+    }
+
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or(Duration::from_secs(0))
-        .as_nanos() as usize
+        .as_nanos() as u64
 }
 
-pub fn random_int(bound: u32) -> u32 {
-    let t = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or(Duration::from_secs(0))
-        .as_nanos();
+pub fn time_seed() -> usize {
+    clock_nanos() as usize
+}
 
-    t as u32 % bound
+pub fn random_int(bound: u32) -> u32 {
+    clock_nanos() as u32 % bound
 }
 
 pub fn random_float(bound: f32) -> f32 {
-    thread_local! {
-        static VAR: std::cell::RefCell<f32> = const { std::cell::RefCell::new(0.2132454) };
-    }
-
     // return random_int(bound as u32) as f32;
 
     VAR.with_borrow_mut(|save| {
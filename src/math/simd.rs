@@ -0,0 +1,135 @@
+// Portable "SIMD" helpers.
+//
+// Coffeevis forbids unsafe code, so std::arch is off the table.
+// Instead, complex numbers are loaded into fixed size arrays
+// in a split (struct of arrays) layout. Every operation below is
+// a plain loop over LANES elements with no dependencies between
+// lanes, which is the shape LLVM reliably turns into vector
+// instructions.
+
+use std::ops::{Add, Mul, Sub};
+
+use super::Cplx;
+
+pub const LANES: usize = 8;
+
+#[derive(Copy, Clone, Default)]
+pub struct CplxLanes {
+    pub re: [f32; LANES],
+    pub im: [f32; LANES],
+}
+
+impl CplxLanes {
+    #[inline(always)]
+    pub fn load(src: &[Cplx]) -> Self {
+        let src = &src[..LANES];
+
+        Self {
+            re: std::array::from_fn(|i| src[i].0),
+            im: std::array::from_fn(|i| src[i].1),
+        }
+    }
+
+    #[inline(always)]
+    pub fn store(self, dst: &mut [Cplx]) {
+        for (i, d) in dst[..LANES].iter_mut().enumerate() {
+            *d = Cplx(self.re[i], self.im[i]);
+        }
+    }
+}
+
+impl Add for CplxLanes {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, other: Self) -> Self {
+        Self {
+            re: std::array::from_fn(|i| self.re[i] + other.re[i]),
+            im: std::array::from_fn(|i| self.im[i] + other.im[i]),
+        }
+    }
+}
+
+impl Sub for CplxLanes {
+    type Output = Self;
+
+    #[inline(always)]
+    fn sub(self, other: Self) -> Self {
+        Self {
+            re: std::array::from_fn(|i| self.re[i] - other.re[i]),
+            im: std::array::from_fn(|i| self.im[i] - other.im[i]),
+        }
+    }
+}
+
+impl Mul for CplxLanes {
+    type Output = Self;
+
+    #[inline(always)]
+    fn mul(self, other: Self) -> Self {
+        Self {
+            re: std::array::from_fn(|i| self.re[i] * other.re[i] - self.im[i] * other.im[i]),
+            im: std::array::from_fn(|i| self.re[i] * other.im[i] + self.im[i] * other.re[i]),
+        }
+    }
+}
+
+/// Largest absolute channel value in the slice, the same as
+/// folding with `Cplx::max` but with independent accumulators.
+pub fn max_abs(a: &[Cplx]) -> f32 {
+    let mut acc = [0.0f32; LANES];
+
+    let mut chunks = a.chunks_exact(LANES / 2);
+
+    // f32::max has to handle NaNs, which stops it from
+    // mapping onto a single vector instruction.
+    let max = |a: f32, b: f32| if b > a { b } else { a };
+
+    for chunk in &mut chunks {
+        for (i, c) in chunk.iter().enumerate() {
+            acc[i * 2] = max(acc[i * 2], c.0.abs());
+            acc[i * 2 + 1] = max(acc[i * 2 + 1], c.1.abs());
+        }
+    }
+
+    let rem = chunks
+        .remainder()
+        .iter()
+        .fold(0.0f32, |a, c| a.max(c.max()));
+
+    acc.iter().fold(rem, |a, &c| a.max(c))
+}
+
+pub fn scale(a: &mut [Cplx], factor: f32) {
+    let mut chunks = a.chunks_exact_mut(LANES);
+
+    for chunk in &mut chunks {
+        for c in chunk.iter_mut() {
+            c.0 *= factor;
+            c.1 *= factor;
+        }
+    }
+
+    for c in chunks.into_remainder() {
+        *c *= factor;
+    }
+}
+
+/// One radix-2 pass over `l` and `r` with the twiddles in `root`,
+/// all three being `halfsize` long.
+///
+/// `halfsize` must be a multiple of `LANES`.
+#[inline(always)]
+pub fn butterfly(l: &mut [Cplx], r: &mut [Cplx], root: &[Cplx]) {
+    let l = l.chunks_exact_mut(LANES);
+    let r = r.chunks_exact_mut(LANES);
+    let root = root.chunks_exact(LANES);
+
+    for ((l, r), w) in l.zip(r).zip(root) {
+        let a = CplxLanes::load(l);
+        let z = CplxLanes::load(r) * CplxLanes::load(w);
+
+        (a + z).store(l);
+        (a - z).store(r);
+    }
+}
@@ -2,30 +2,63 @@ use crossterm::{
     cursor::{self, Hide, Show},
     event::{Event, KeyCode, poll, read},
     queue,
-    style::{Attribute, Color, Print, SetAttribute, SetForegroundColor},
+    style::{Attribute, Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor},
     terminal::{
         Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode,
-        enable_raw_mode, size,
+        enable_raw_mode, size, window_size,
     },
 };
 
-use arrayvec::ArrayString;
-
-use std::io::{Error, Stdout, Write, stdout};
+use std::io::{Error, Write, stdout};
+use std::time::Instant;
 
 use crate::{
     data::*,
-    graphics::{Argb, Painter, Pixel, blend::grayb},
+    graphics::{
+        Argb, Painter, Pixel,
+        blend::grayb,
+        kitty,
+        palette::{ColorDepth, Dither},
+        sixel,
+    },
     modes::Mode,
 };
 
-pub type Flusher = fn(&Program, &Painter, &mut Stdout);
+pub type Flusher = fn(&Program, &Painter, &mut Screen);
 
 const ERROR: u8 = 6;
 
 const CHARSET_OPAC_EXP: &[u8] = b" `.-':_,^=;><+!rc*/z?sLTv)J7(|Fi{C}fI31tlu[neoZ\
     5Yxjya]2ESwqkP6h9d4VpOGbUAKXHm8RD#$Bg0MNWQ%&@";
 
+/// Indexed by which of the 2x3 cells are lit, bit 0 being
+/// the top left one, row by row.
+const SEXTANTS: [char; 64] = [
+    ' ', '🬀', '🬁', '🬂', '🬃', '🬄', '🬅', '🬆', '🬇', '🬈', '🬉', '🬊', '🬋', '🬌', '🬍', '🬎', '🬏', '🬐', '🬑',
+    '🬒', '🬓', '▌', '🬔', '🬕', '🬖', '🬗', '🬘', '🬙', '🬚', '🬛', '🬜', '🬝', '🬞', '🬟', '🬠', '🬡', '🬢', '🬣',
+    '🬤', '🬥', '🬦', '🬧', '▐', '🬨', '🬩', '🬪', '🬫', '🬬', '🬭', '🬮', '🬯', '🬰', '🬱', '🬲', '🬳', '🬴', '🬵',
+    '🬶', '🬷', '🬸', '🬹', '🬺', '🬻', '█',
+];
+
+/// Same for 2x4 cells. Patterns that already had a block
+/// character do not get an octant of their own.
+const OCTANTS: [char; 256] = [
+    ' ', '𜺨', '𜺫', '🮂', '𜴀', '▘', '𜴁', '𜴂', '𜴃', '𜴄', '▝', '𜴅', '𜴆', '𜴇', '𜴈', '▀', '𜴉', '𜴊', '𜴋',
+    '𜴌', '🯦', '𜴍', '𜴎', '𜴏', '𜴐', '𜴑', '𜴒', '𜴓', '𜴔', '𜴕', '𜴖', '𜴗', '𜴘', '𜴙', '𜴚', '𜴛', '𜴜', '𜴝',
+    '𜴞', '𜴟', '🯧', '𜴠', '𜴡', '𜴢', '𜴣', '𜴤', '𜴥', '𜴦', '𜴧', '𜴨', '𜴩', '𜴪', '𜴫', '𜴬', '𜴭', '𜴮', '𜴯',
+    '𜴰', '𜴱', '𜴲', '𜴳', '𜴴', '𜴵', '🮅', '𜺣', '𜴶', '𜴷', '𜴸', '𜴹', '𜴺', '𜴻', '𜴼', '𜴽', '𜴾', '𜴿', '𜵀',
+    '𜵁', '𜵂', '𜵃', '𜵄', '▖', '𜵅', '𜵆', '𜵇', '𜵈', '▌', '𜵉', '𜵊', '𜵋', '𜵌', '▞', '𜵍', '𜵎', '𜵏', '𜵐',
+    '▛', '𜵑', '𜵒', '𜵓', '𜵔', '𜵕', '𜵖', '𜵗', '𜵘', '𜵙', '𜵚', '𜵛', '𜵜', '𜵝', '𜵞', '𜵟', '𜵠', '𜵡', '𜵢',
+    '𜵣', '𜵤', '𜵥', '𜵦', '𜵧', '𜵨', '𜵩', '𜵪', '𜵫', '𜵬', '𜵭', '𜵮', '𜵯', '𜵰', '𜺠', '𜵱', '𜵲', '𜵳', '𜵴',
+    '𜵵', '𜵶', '𜵷', '𜵸', '𜵹', '𜵺', '𜵻', '𜵼', '𜵽', '𜵾', '𜵿', '𜶀', '𜶁', '𜶂', '𜶃', '𜶄', '𜶅', '𜶆', '𜶇',
+    '𜶈', '𜶉', '𜶊', '𜶋', '𜶌', '𜶍', '𜶎', '𜶏', '▗', '𜶐', '𜶑', '𜶒', '𜶓', '▚', '𜶔', '𜶕', '𜶖', '𜶗', '▐',
+    '𜶘', '𜶙', '𜶚', '𜶛', '▜', '𜶜', '𜶝', '𜶞', '𜶟', '𜶠', '𜶡', '𜶢', '𜶣', '𜶤', '𜶥', '𜶦', '𜶧', '𜶨', '𜶩',
+    '𜶪', '𜶫', '▂', '𜶬', '𜶭', '𜶮', '𜶯', '𜶰', '𜶱', '𜶲', '𜶳', '𜶴', '𜶵', '𜶶', '𜶷', '𜶸', '𜶹', '𜶺', '𜶻',
+    '𜶼', '𜶽', '𜶾', '𜶿', '𜷀', '𜷁', '𜷂', '𜷃', '𜷄', '𜷅', '𜷆', '𜷇', '𜷈', '𜷉', '𜷊', '𜷋', '𜷌', '𜷍', '𜷎',
+    '𜷏', '𜷐', '𜷑', '𜷒', '𜷓', '𜷔', '𜷕', '𜷖', '𜷗', '𜷘', '𜷙', '𜷚', '▄', '𜷛', '𜷜', '𜷝', '𜷞', '▙', '𜷟',
+    '𜷠', '𜷡', '𜷢', '▟', '𜷣', '▆', '𜷤', '𜷥', '█',
+];
+
 pub struct ConsoleProps {
     pub width: u16,
     pub height: u16,
@@ -35,14 +68,56 @@ pub struct ConsoleProps {
 
     pub max_width: u16,
     pub max_height: u16,
+
+    /// Pixels per character cell, for sixel and kitty.
+    pub cell_width: u16,
+    pub cell_height: u16,
+
+    pub colors: ColorDepth,
+    pub dither: Dither,
+
+    /// Show how many bytes each frame takes.
+    pub stats: bool,
+
+    /// A status line at the top or bottom of the terminal.
+    pub hud: bool,
+    pub hud_top: bool,
+
+    /// Rows to draw in under the prompt, instead of taking over the screen.
+    pub inline: Option<u16>,
+
+    /// First row of the terminal that is drawn on.
+    pub top: u16,
+
+    /// Set when the terminal was cleared, so that the next frame is sent in full.
+    pub cleared: bool,
+
     pub flusher: Flusher,
 }
 
 impl ConsoleProps {
-    pub fn set_size(&mut self, s: (u16, u16), m: Mode) {
+    pub fn set_size(&mut self, mut s: (u16, u16), m: Mode) {
+        // Kept on screen when the terminal gets shorter.
+        if let Some(rows) = self.inline {
+            self.top = self.top.min(s.1.saturating_sub(rows));
+            s.1 = s.1.min(rows);
+        }
+
         self.physical_width = s.0;
         self.physical_height = s.1;
 
+        // Terminals that do not report their size in pixels get
+        // a common cell size.
+        if m.is_pixels()
+            && let Ok(w) = window_size()
+            && w.width > 0
+            && w.columns > 0
+            && w.rows > 0
+        {
+            self.cell_width = (w.width / w.columns).max(1);
+            self.cell_height = (w.height / w.rows).max(1);
+        }
+
         let s = self.rescale(s, m);
 
         self.width = s.0;
@@ -59,10 +134,18 @@ impl ConsoleProps {
         s.1 = s.1.min(self.max_height);
 
         match m {
-            Mode::ConBrail => {
+            Mode::ConBrail | Mode::ConOctant => {
                 s.0 *= 2;
                 s.1 *= 4;
             }
+            Mode::ConSextant => {
+                s.0 *= 2;
+                s.1 *= 3;
+            }
+            Mode::ConSixel | Mode::ConKitty => {
+                s.0 = (s.0 * self.cell_width).min(MAX_WIDTH);
+                s.1 = (s.1 * self.cell_height).min(MAX_HEIGHT);
+            }
             _ => {
                 s.1 *= 2;
             }
@@ -72,66 +155,234 @@ impl ConsoleProps {
     }
 }
 
-struct ColoredString {
-    pub string: ArrayString<{ MAX_CON_WIDTH as usize }>,
-    pub fg: Argb,
-    error: u8,
+/// A character on the terminal and its colors.
+#[derive(Clone, Copy, PartialEq)]
+struct TermCell {
+    ch: char,
+    fg: Argb,
+    bg: Option<Argb>,
 }
 
-/// Compress similar pixels into one string with the same
-/// color. Hopefully this reduces IO performance cost.
-impl ColoredString {
-    pub fn new(ch: char, fg: Argb, error: u8) -> Self {
-        let mut string = ArrayString::new();
-        string.push(ch);
+const BLANK: TermCell = TermCell {
+    ch: ' ',
+    fg: 0,
+    bg: None,
+};
+
+/// Printing this many unchanged cells again is cheaper than
+/// moving the cursor over them.
+const MAX_REPRINT: usize = 3;
+
+/// Colors last sent to the terminal.
+struct Pen {
+    colors: ColorDepth,
+    fg: Option<Argb>,
+    bg: Option<Argb>,
+}
+
+impl Pen {
+    fn close(&self, a: Argb, b: Argb) -> bool {
+        let [_, r, g, b_] = a.decompose();
+        let [_, nr, ng, nb] = b.decompose();
 
-        Self { string, fg, error }
+        match self.colors {
+            ColorDepth::TrueColor => {
+                r.abs_diff(nr) <= ERROR && g.abs_diff(ng) <= ERROR && b_.abs_diff(nb) <= ERROR
+            }
+            ColorDepth::Mono => true,
+            depth => depth.quantize(a) == depth.quantize(b),
+        }
     }
 
-    pub fn default() -> Self {
-        ColoredString::new('\0', Argb::black(), ERROR)
+    /// Whether `a` would look the same as `b` on the terminal.
+    fn same(&self, a: TermCell, b: TermCell) -> bool {
+        let bg = match (a.bg, b.bg) {
+            (None, None) => true,
+            (Some(a), Some(b)) => self.close(a, b),
+            _ => self.colors == ColorDepth::Mono,
+        };
+
+        a.ch == b.ch && bg && (a.ch == ' ' || self.close(a.fg, b.fg))
     }
 
-    pub fn append(&mut self, ch: char, fg: Argb) -> bool {
-        let [_, r, g, b] = self.fg.decompose();
-        let [_, nr, ng, nb] = fg.decompose();
+    fn queue_color(&self, out: &mut Vec<u8>, c: Argb, background: bool) {
+        let color = match self.colors {
+            ColorDepth::Mono => return,
+
+            // Plain SGR codes, for terminals that do not know the 256 color ones.
+            ColorDepth::Ansi16 => {
+                let i = self.colors.quantize(c).unwrap_or(0);
+                let base = if i < 8 { 30 } else { 90 };
+                let code = base + background as u8 * 10 + i % 8;
+                let _ = queue!(out, Print(format!("\x1b[{code}m")));
+                return;
+            }
+
+            ColorDepth::Xterm256 => Color::AnsiValue(self.colors.quantize(c).unwrap_or(0)),
+
+            ColorDepth::TrueColor => {
+                let [_, r, g, b] = c.decompose();
+                Color::Rgb { r, g, b }
+            }
+        };
 
-        let er = r.abs_diff(nr);
-        let eg = g.abs_diff(ng);
-        let eb = b.abs_diff(nb);
+        let _ = if background {
+            queue!(out, SetBackgroundColor(color))
+        } else {
+            queue!(out, SetForegroundColor(color))
+        };
+    }
 
-        if er <= self.error && eg <= self.error && eb <= self.error {
-            _ = self.string.try_push(ch);
-            return true;
+    /// Prints `cell` at the cursor, changing colors only where needed.
+    fn print(&mut self, out: &mut Vec<u8>, cell: TermCell) {
+        if self.colors != ColorDepth::Mono {
+            match (self.bg, cell.bg) {
+                (Some(a), Some(b)) if self.close(a, b) => {}
+                (None, None) => {}
+                (_, Some(c)) => {
+                    self.queue_color(out, c, true);
+                    self.bg = cell.bg;
+                }
+                (_, None) => {
+                    let _ = queue!(out, SetBackgroundColor(Color::Reset));
+                    self.bg = None;
+                }
+            }
+        }
+
+        if cell.ch != ' ' && !self.fg.is_some_and(|fg| self.close(fg, cell.fg)) {
+            self.queue_color(out, cell.fg, false);
+            self.fg = Some(cell.fg);
         }
 
-        false
+        let _ = queue!(out, Print(cell.ch));
     }
+}
+
+/// The character grid of the terminal. Flushers draw a frame into it,
+/// and only the cells that changed since the last frame get sent.
+pub struct Screen {
+    width: usize,
+    height: usize,
 
-    pub fn replace(&mut self, ch: char, fg: Argb) {
-        self.string.clear();
-        self.string.push(ch);
-        self.fg = fg;
+    cells: Vec<TermCell>,
+
+    /// What the terminal shows right now.
+    shown: Vec<TermCell>,
+
+    /// Terminal row the grid starts at.
+    top: u16,
+
+    cursor: (usize, usize),
+
+    /// Bytes for the terminal, written out once per frame.
+    pub out: Vec<u8>,
+}
+
+impl Screen {
+    pub fn new() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+            shown: Vec::new(),
+            top: 0,
+            cursor: (0, 0),
+            out: Vec::new(),
+        }
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        (self.width as u16, self.height as u16)
     }
 
-    fn push_pixel(&mut self, ch: char, fg: Argb) {
-        if !self.append(ch, fg) {
-            self.queue_print();
-            self.replace(ch, fg);
+    /// Forgets what was drawn, for after the terminal was cleared or resized.
+    pub fn invalidate(&mut self, (width, height): (u16, u16), top: u16) {
+        self.width = width as usize;
+        self.height = height as usize;
+        self.top = top;
+
+        for grid in [&mut self.cells, &mut self.shown] {
+            grid.clear();
+            grid.resize(self.width * self.height, BLANK);
         }
     }
 
-    fn queue_print(&mut self) {
-        let [_, r, g, b] = self.fg.decompose();
-        let _ = queue!(
-            stdout(),
-            SetForegroundColor(Color::Rgb { r, g, b }),
-            Print(&self.string)
-        );
+    pub fn move_to(&mut self, x: u16, y: u16) {
+        self.cursor = (x as usize, y as usize);
     }
 
-    fn reset(&mut self) {
-        self.replace('\0', Argb::black());
+    /// Puts a character at the cursor and moves it to the right.
+    pub fn push(&mut self, ch: char, fg: Argb, bg: Option<Argb>) {
+        let (x, y) = self.cursor;
+        self.cursor.0 += 1;
+
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        self.cells[y * self.width + x] = if ch == ' ' && bg.is_none() {
+            BLANK
+        } else {
+            TermCell { ch, fg, bg }
+        };
+    }
+
+    pub fn print(&mut self, text: &str, fg: Argb, bg: Option<Argb>) {
+        text.chars().for_each(|ch| self.push(ch, fg, bg));
+    }
+
+    /// Queues the cells that changed into `out`, and starts a blank frame.
+    pub fn flush(&mut self, colors: ColorDepth) {
+        let mut pen = Pen {
+            colors,
+            fg: None,
+            bg: None,
+        };
+
+        // Where the terminal cursor is, if known.
+        let mut cursor = None;
+
+        for y in 0..self.height {
+            let row = y * self.width;
+
+            for x in 0..self.width {
+                let cell = self.cells[row + x];
+
+                if pen.same(cell, self.shown[row + x]) {
+                    continue;
+                }
+
+                match cursor {
+                    Some((cx, cy)) if cy == y && cx == x => {}
+
+                    Some((cx, cy)) if cy == y && cx < x && x - cx <= MAX_REPRINT => {
+                        for i in row + cx..row + x {
+                            pen.print(&mut self.out, self.cells[i]);
+                            self.shown[i] = self.cells[i];
+                        }
+                    }
+
+                    Some((cx, cy)) if cy == y && cx < x => {
+                        let _ = queue!(self.out, cursor::MoveRight((x - cx) as u16));
+                    }
+
+                    _ => {
+                        let _ = queue!(self.out, cursor::MoveTo(x as u16, self.top + y as u16));
+                    }
+                }
+
+                pen.print(&mut self.out, cell);
+                self.shown[row + x] = cell;
+                cursor = Some((x + 1, y));
+            }
+        }
+
+        if pen.bg.is_some() {
+            let _ = queue!(self.out, SetBackgroundColor(Color::Reset));
+        }
+
+        self.cells.fill(BLANK);
     }
 }
 
@@ -142,6 +393,11 @@ impl Mode {
         match *self {
             Mode::ConAscii => Program::print_ascii,
             Mode::ConBrail => Program::print_brail,
+            Mode::ConHalf => Program::print_half,
+            Mode::ConSextant => Program::print_sextant,
+            Mode::ConOctant => Program::print_octant,
+            Mode::ConSixel => Program::print_sixel,
+            Mode::ConKitty => Program::print_kitty,
             _ => Program::print_block,
         }
     }
@@ -149,7 +405,20 @@ impl Mode {
 
 impl Program {
     pub fn clear_con(&mut self) {
-        let _ = queue!(std::io::stdout(), Clear(ClearType::All));
+        let _ = match self.console_props.inline {
+            None => queue!(std::io::stdout(), Clear(ClearType::All)),
+            Some(_) => queue!(
+                std::io::stdout(),
+                cursor::MoveTo(0, self.console_props.top),
+                Clear(ClearType::FromCursorDown)
+            ),
+        };
+        self.console_props.cleared = true;
+
+        // Images are not text and survive clearing.
+        if Mode::ConKitty.is_supported() {
+            let _ = queue!(std::io::stdout(), Print(kitty::delete()));
+        }
     }
 
     pub fn switch_con_mode(&mut self) {
@@ -186,11 +455,24 @@ impl Program {
         )
     }
 
+    /// What is running, for the status line.
+    fn hud_text(&self, fps: f32, level: f32) -> String {
+        let db = 20.0 * level.max(1e-5).log10();
+
+        format!(
+            " {} | {} | {fps:.0} fps | auto switch {} | {} | {db:.0} dB",
+            self.vis_name(),
+            self.mode().get_name(),
+            if self.auto_switch() { "on" } else { "off" },
+            crate::audio::device_name(),
+        )
+    }
+
     pub fn console_size(&self) -> (u16, u16) {
         (self.console_props.width, self.console_props.height)
     }
 
-    pub fn print_ascii(&self, pix: &Painter, stdout: &mut Stdout) {
+    pub fn print_ascii(&self, pix: &Painter, screen: &mut Screen) {
         let center = self.get_center(
             pix.logical_width() as u16,
             pix.logical_height() as u16,
@@ -198,11 +480,9 @@ impl Program {
             4,
         );
 
-        let mut line = ColoredString::default();
-
         for y in (0..pix.logical_height()).step_by(2) {
             let cy = center.1 + y as u16 / 2;
-            let _ = queue!(stdout, cursor::MoveTo(center.0, cy));
+            screen.move_to(center.0, cy);
 
             for x in 0..pix.logical_width() {
                 let base = pix.logical_width() * y + x;
@@ -219,15 +499,12 @@ impl Program {
 
                 let alpha_char = to_ascii_art(CHARSET_OPAC_EXP, lum as usize);
 
-                line.push_pixel(alpha_char, Argb::compose([0, r, g, b]));
+                screen.push(alpha_char, Argb::compose([0, r, g, b]), None);
             }
-
-            line.queue_print();
-            line.reset();
         }
     }
 
-    pub fn print_block(&self, pix: &Painter, stdout: &mut Stdout) {
+    pub fn print_block(&self, pix: &Painter, screen: &mut Screen) {
         let center = self.get_center(
             pix.logical_width() as u16,
             pix.logical_height() as u16,
@@ -235,11 +512,9 @@ impl Program {
             4,
         );
 
-        let mut line = ColoredString::default();
-
         for y_base in (0..pix.logical_height()).step_by(2) {
             let cy = center.1 + y_base as u16 / 2;
-            let _ = queue!(stdout, cursor::MoveTo(center.0, cy));
+            screen.move_to(center.0, cy);
 
             for x_base in 0..pix.logical_width() {
                 let idx_base = y_base * pix.logical_width() + x_base;
@@ -263,28 +538,102 @@ impl Program {
 
                 let block_char = ([' ', '▄', '▀', '█'])[bx as usize];
 
-                line.push_pixel(block_char, Argb::compose([0, r, g, b]));
+                screen.push(block_char, Argb::compose([0, r, g, b]), None);
             }
+        }
+    }
+
+    /// Upper half blocks, the top pixel in the foreground
+    /// and the bottom one in the background.
+    pub fn print_half(&self, pix: &Painter, screen: &mut Screen) {
+        let center = self.get_center(
+            pix.logical_width() as u16,
+            pix.logical_height() as u16,
+            2,
+            4,
+        );
 
-            line.queue_print();
-            line.reset();
+        let width = pix.logical_width();
+        for y_base in (0..pix.logical_height()).step_by(2) {
+            let cy = center.1 + y_base as u16 / 2;
+            screen.move_to(center.0, cy);
+
+            for x in 0..width {
+                let idx = y_base * width + x;
+                let top = pix.pixel(idx);
+                let bottom = pix.pixel(idx + width);
+
+                // Without colors, whichever halves are lit.
+                if self.console_props.colors == ColorDepth::Mono {
+                    let lit = |c: Argb| {
+                        let [_, r, g, b] = c.decompose();
+                        (grayb(r, g, b) >= 48) as usize
+                    };
+
+                    screen.push([' ', '▄', '▀', '█'][lit(top) << 1 | lit(bottom)], top, None);
+                    continue;
+                }
+
+                screen.push('▀', top, Some(bottom));
+            }
         }
     }
 
-    pub fn print_brail(&self, pix: &Painter, stdout: &mut Stdout) {
+    /// Solid blocks of 2 by `rows` pixels, one of `table` for each.
+    fn print_mosaic(&self, pix: &Painter, screen: &mut Screen, rows: usize, table: &[char]) {
         let center = self.get_center(
             pix.logical_width() as u16,
             pix.logical_height() as u16,
             4,
-            8,
+            rows as u16 * 2,
         );
 
-        let mut line = ColoredString::default();
+        let width = pix.logical_width();
+        for y_base in (0..pix.logical_height()).step_by(rows) {
+            let cy = center.1 + (y_base / rows) as u16;
+            screen.move_to(center.0, cy);
+
+            for x_base in (0..width).step_by(2) {
+                let idx_base = y_base * width + x_base;
+
+                let [_, mut r, mut g, mut b] = pix.pixel(idx_base).to_be_bytes();
+
+                let bx = (0..rows * 2).fold(0usize, |acc, i| {
+                    let [_, pr, pg, pb] =
+                        pix.pixel(idx_base + (i / 2) * width + i % 2).to_be_bytes();
+
+                    r = r.max(pr);
+                    g = g.max(pg);
+                    b = b.max(pb);
+
+                    acc | (((grayb(pr, pg, pb) > 36) as usize) << i)
+                });
+
+                screen.push(table[bx], Argb::compose([0, r, g, b]), None);
+            }
+        }
+    }
+
+    pub fn print_sextant(&self, pix: &Painter, screen: &mut Screen) {
+        self.print_mosaic(pix, screen, 3, &SEXTANTS);
+    }
+
+    pub fn print_octant(&self, pix: &Painter, screen: &mut Screen) {
+        self.print_mosaic(pix, screen, 4, &OCTANTS);
+    }
+
+    pub fn print_brail(&self, pix: &Painter, screen: &mut Screen) {
+        let center = self.get_center(
+            pix.logical_width() as u16,
+            pix.logical_height() as u16,
+            4,
+            8,
+        );
 
         for y_base in (0..pix.logical_height()).step_by(4) {
             let cy = center.1 + y_base as u16 / 4;
 
-            let _ = queue!(stdout, cursor::MoveTo(center.0, cy));
+            screen.move_to(center.0, cy);
 
             for x_base in (0..pix.logical_width()).step_by(2) {
                 let idx_base = y_base * pix.logical_width() + x_base;
@@ -312,14 +661,44 @@ impl Program {
 						// be used to increase performance.
 					}) as u32;
 
-                line.push_pixel(
+                screen.push(
                     char::from_u32(bx).unwrap_or('⠀'),
                     Argb::compose([0, r, g, b]),
+                    None,
                 );
             }
+        }
+    }
+}
+
+impl Program {
+    /// Top left cell to draw an image of `pix` at, centered.
+    fn image_origin(&self, pix: &Painter) -> (u16, u16) {
+        let props = &self.console_props;
+        let cols = (pix.logical_width() as u16).div_ceil(props.cell_width);
+        let rows = (pix.logical_height() as u16).div_ceil(props.cell_height);
+
+        (
+            props.physical_width.saturating_sub(cols) / 2,
+            props.top + props.physical_height.saturating_sub(rows) / 2,
+        )
+    }
 
-            line.queue_print();
-            line.reset();
+    pub fn print_sixel(&self, pix: &Painter, screen: &mut Screen) {
+        let (x, y) = self.image_origin(pix);
+
+        let _ = queue!(
+            screen.out,
+            cursor::MoveTo(x, y),
+            Print(sixel::encode(&pix.to_image()))
+        );
+    }
+
+    pub fn print_kitty(&self, pix: &Painter, screen: &mut Screen) {
+        let (x, y) = self.image_origin(pix);
+
+        if let Some(image) = kitty::encode(&pix.to_image()) {
+            let _ = queue!(screen.out, cursor::MoveTo(x, y), Print(image));
         }
     }
 }
@@ -373,6 +752,16 @@ pub fn control_key_events_con(prog: &mut Program, exit: &mut bool) -> Result<boo
 
                     KeyCode::Char('\\') => prog.toggle_auto_switch(),
 
+                    KeyCode::Tab => prog.cycle_focus(),
+
+                    KeyCode::Char('t') => prog.cycle_theme(),
+
+                    KeyCode::Char('p') => prog.screenshot(),
+
+                    KeyCode::Char('g') => prog.toggle_recording(),
+                    KeyCode::Char('f') => prog.toggle_fps(),
+                    KeyCode::Char('h') => prog.toggle_hints(),
+
                     KeyCode::Char('.') => {
                         prog.switch_con_mode();
                         prog.clear_con();
@@ -380,6 +769,8 @@ pub fn control_key_events_con(prog: &mut Program, exit: &mut bool) -> Result<boo
 
                     KeyCode::Char('/') => prog.reset_parameters(),
 
+                    KeyCode::Char('i') => prog.console_props.hud ^= true,
+
                     _ => {}
                 }
                 return Ok(true);
@@ -400,6 +791,9 @@ pub fn control_key_events_con(prog: &mut Program, exit: &mut bool) -> Result<boo
 pub fn con_main(mut prog: Program) -> std::io::Result<()> {
     prog.print_startup_info();
 
+    // Before anything reads input.
+    let _ = super::probe::graphics();
+
     let mut stdout = stdout();
 
     enable_raw_mode()?;
@@ -407,16 +801,36 @@ pub fn con_main(mut prog: Program) -> std::io::Result<()> {
 
     let mut exit: bool = false;
 
-    let _ = queue!(
-        stdout,
-        EnterAlternateScreen,
-        Hide,
-        SetAttribute(Attribute::Bold)
-    );
-
     let size = size()?;
+
+    match prog.console_props.inline {
+        None => queue!(stdout, EnterAlternateScreen)?,
+
+        // Scrolls up to make room under the prompt if needed.
+        Some(rows) => {
+            let rows = rows.min(size.1);
+            queue!(
+                stdout,
+                Print("\r\n".repeat(rows as usize)),
+                cursor::MoveUp(rows)
+            )?;
+            stdout.flush()?;
+
+            prog.console_props.top = cursor::position().map(|p| p.1).unwrap_or(size.1 - rows);
+        }
+    }
+
+    let _ = queue!(stdout, Hide, SetAttribute(Attribute::Bold));
+
     prog.update_size(size);
     let mut raw_buffer = vec![0x0; (prog.console_props.width * prog.console_props.height) as usize];
+    let mut dithered = Vec::new();
+
+    let mut screen = Screen::new();
+    let (mut sent, mut average) = (0, 0.0f32);
+
+    let mut last_frame = Instant::now();
+    let mut fps = 0.0f32;
 
     while !exit {
         let forcedraw = control_key_events_con(&mut prog, &mut exit)?;
@@ -433,21 +847,95 @@ pub fn con_main(mut prog: Program) -> std::io::Result<()> {
             0,
         );
 
-        let mut pix = Painter::from(
-            &mut raw_buffer,
+        let (w, h) = (
             prog.console_props.width as usize,
             prog.console_props.height as usize,
-            1,
-            0,
-            false,
         );
-        (prog.console_props.flusher)(&prog, &pix, &mut std::io::stdout());
+
+        // Dithered on a copy, so that visualizers keep drawing over
+        // their own frames.
+        let (colors, dither) = (prog.console_props.colors, prog.console_props.dither);
+
+        let shown = if dither != Dither::None && !prog.mode().is_pixels() {
+            dithered.clone_from(&raw_buffer);
+            dither.apply(colors, &mut dithered, w);
+            &mut dithered
+        } else {
+            &mut raw_buffer
+        };
+
+        let terminal = (
+            prog.console_props.physical_width,
+            prog.console_props.physical_height,
+        );
+
+        if std::mem::take(&mut prog.console_props.cleared) || screen.size() != terminal {
+            screen.invalidate(terminal, prog.console_props.top);
+        }
+
+        let pix = Painter::from(shown, w, h, 1, 0, false);
+        (prog.console_props.flusher)(&prog, &pix, &mut screen);
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(last_frame).as_secs_f32();
+        last_frame = now;
+        fps = fps * 0.9 + 0.1 / elapsed.max(1e-3);
+
+        // Drawn over the frame, which stays centered on the whole terminal.
+        let props = &prog.console_props;
+        let hud_row = if props.hud_top {
+            0
+        } else {
+            terminal.1.saturating_sub(1)
+        };
+
+        if props.hud {
+            let text = prog.hud_text(fps, buf.level());
+            let width = terminal.0 as usize;
+
+            screen.move_to(0, hud_row);
+            screen.print(
+                &format!("{text:<width$}"),
+                Argb::white(),
+                Some(0xFF_30_30_30),
+            );
+        }
+
+        if props.stats {
+            screen.move_to(0, (props.hud && props.hud_top) as u16);
+            screen.print(
+                &format!("{sent} B/frame, {average:.0} on average "),
+                Argb::white(),
+                None,
+            );
+        }
+
+        screen.flush(colors);
+
+        sent = screen.out.len();
+        average = average * 0.9 + sent as f32 * 0.1;
+
+        let _ = stdout.write_all(&screen.out);
+        screen.out.clear();
+
+        let mut pix = Painter::from(&mut raw_buffer, w, h, 1, 0, false);
         prog.render(&mut pix, &mut buf);
 
         let _ = stdout.flush();
     }
 
-    queue!(stdout, LeaveAlternateScreen, Show)?;
+    prog.clear_con();
+
+    // The prompt comes back where the strip was.
+    match prog.console_props.inline {
+        None => queue!(stdout, LeaveAlternateScreen, Show)?,
+        Some(_) => queue!(
+            stdout,
+            cursor::MoveTo(0, prog.console_props.top),
+            SetAttribute(Attribute::Reset),
+            Show
+        )?,
+    }
 
     disable_raw_mode()?;
 
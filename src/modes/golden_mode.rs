@@ -0,0 +1,311 @@
+use std::f32::consts::TAU;
+use std::path::{Path, PathBuf};
+
+use crate::audio::get_buf;
+use crate::data::{
+    SAMPLE_RATE,
+    log::{error, info},
+};
+use crate::graphics::{Argb, Painter, Pixel, image::Image};
+use crate::modes::render_mode;
+use crate::visualizers::VisList;
+
+const DEFAULT_DIR: &str = "tests/golden";
+
+/// Logical sizes and scales. Scaled cases draw one field per frame
+/// like the default interlaced window, which takes the other paths
+/// through the drawing code.
+const CASES: [((u16, u16), u8); 4] = [((64, 64), 1), ((128, 72), 1), ((64, 64), 2), ((96, 54), 2)];
+
+/// Frames compared against the references, counted from 1.
+const CHECKPOINTS: [usize; 3] = [10, 30, 60];
+
+const FPS: usize = 60;
+
+/// Default largest color distance (0 to 255) that still counts as
+/// the same pixel, to let rounding differ between platforms.
+const DEFAULT_TOLERANCE: f32 = 12.0;
+
+/// Share of pixels in a frame that may be further apart than the tolerance.
+const MAX_BAD_PIXELS: f32 = 0.005;
+
+struct GoldenProps {
+    dir: PathBuf,
+    update: bool,
+    tolerance: f32,
+    only: Option<String>,
+}
+
+impl GoldenProps {
+    fn parse(args: &[String]) -> Self {
+        let mut props = Self {
+            dir: PathBuf::from(DEFAULT_DIR),
+            update: false,
+            tolerance: DEFAULT_TOLERANCE,
+            only: None,
+        };
+
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--dir" => {
+                    let dir = args.next().expect("Argument error: Expected directory.");
+                    props.dir = PathBuf::from(dir);
+                }
+
+                "--update" => props.update = true,
+
+                "--tolerance" => {
+                    props.tolerance = args
+                        .next()
+                        .expect("Argument error: Expected value for tolerance.")
+                        .parse::<f32>()
+                        .expect("Argument error: Invalid value.");
+                }
+
+                "--vis" => {
+                    let name = args
+                        .next()
+                        .expect("Argument error: Expected name of visualizer");
+
+                    props.only = Some(name.clone());
+                }
+
+                _ => panic!("Argument error: Unknown option {arg} for golden."),
+            }
+        }
+
+        props
+    }
+}
+
+/// Two seconds of a kick drum and a noise snare under a rising sweep,
+/// over a swelling sub bass, wider on the right channel. Stereo
+/// interleaved, the same on every machine.
+fn signal(frame: usize) -> Vec<f32> {
+    let len = SAMPLE_RATE / FPS;
+    let start = frame * len;
+
+    (start..start + len)
+        .flat_map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+
+            // 110 Hz to 1760 Hz over two seconds, exponentially.
+            let phase = 110.0 * 2.0 / 4.0f32.ln() * (4.0f32.powf(t) - 1.0);
+            let sweep = (TAU * phase).sin() * 0.4;
+
+            let beat = t % 0.5;
+            let kick = (TAU * 55.0 * beat).sin() * (-beat * 12.0).exp() * 0.6;
+
+            // White noise from a hash of the sample index, on the off beats.
+            let noise = (i as u32).wrapping_mul(2654435761) >> 8;
+            let noise = noise as f32 / (1 << 23) as f32 - 1.0;
+            let offbeat = (t + 0.25) % 0.5;
+            let snare = noise * (-offbeat * 20.0).exp() * 0.3;
+
+            let sub = (TAU * 37.0 * t).sin() * 0.3 * t;
+
+            let l = sweep + kick + snare + sub;
+            let r = (TAU * phase + 0.8).sin() * 0.3 + kick + snare + sub;
+            [l, r]
+        })
+        .collect()
+}
+
+/// Renders `name` the way `render` mode would and returns the checkpoint frames.
+fn render_case(name: &str, (w, h): (u16, u16), scale: u8) -> Vec<Image> {
+    let args: Vec<String> = [
+        "--vis",
+        name,
+        "--size",
+        &format!("{w}x{h}"),
+        "--scale",
+        &scale.to_string(),
+        "--fps",
+        &FPS.to_string(),
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+
+    let mut prog = render_mode::program(&args);
+
+    get_buf().clear();
+    crate::math::rng::fix_clock(0);
+
+    let s = scale as usize;
+    let (w, h) = (w as usize * s, h as usize * s);
+    let mut buffer = vec![0; w * h];
+    let mut frames = Vec::new();
+
+    for frame in 1..=CHECKPOINTS[CHECKPOINTS.len() - 1] {
+        let mut audiobuf = get_buf();
+        audiobuf.read_from_input(&signal(frame - 1));
+
+        let field = (frame % s) as u8;
+        let mut pix = Painter::from(&mut buffer, w, h, scale, field, scale == 1);
+        prog.render(&mut pix, &mut audiobuf);
+
+        if CHECKPOINTS.contains(&frame) {
+            frames.push(pix.to_image());
+        }
+    }
+
+    frames
+}
+
+/// `spectrum_64x64`, or `spectrum_64x64@2` when scaled.
+fn case_name(name: &str, (w, h): (u16, u16), scale: u8) -> String {
+    let name = name.to_lowercase().replace(' ', "_");
+
+    match scale {
+        1 => format!("{name}_{w}x{h}"),
+        s => format!("{name}_{w}x{h}@{s}"),
+    }
+}
+
+/// Color distance weighted by how sensitive the eye is to each
+/// channel ("redmean"), scaled to 0 to 255.
+fn distance(a: Argb, b: Argb) -> f32 {
+    let [_, r1, g1, b1] = a.decompose();
+    let [_, r2, g2, b2] = b.decompose();
+
+    let mean = (r1 as f32 + r2 as f32) / 2.0;
+    let dr = r1 as f32 - r2 as f32;
+    let dg = g1 as f32 - g2 as f32;
+    let db = b1 as f32 - b2 as f32;
+
+    let d =
+        (2.0 + mean / 256.0) * dr * dr + 4.0 * dg * dg + (2.0 + (255.0 - mean) / 256.0) * db * db;
+
+    d.sqrt() / 3.0
+}
+
+/// Pixels that are too far apart, in red over a dimmed copy of `expected`.
+fn compare(expected: &Image, actual: &Image, tolerance: f32) -> (usize, Image) {
+    let (w, h) = (expected.width(), expected.height());
+    let mut bad = 0;
+
+    let data = (0..w * h)
+        .map(|i| {
+            let (e, a) = (expected.pixel(i % w, i / w), actual.pixel(i % w, i / w));
+
+            if distance(e, a) > tolerance {
+                bad += 1;
+                return Argb::compose([0xFF, 0xFF, 0, 0]);
+            }
+
+            let [_, r, g, b] = e.decompose();
+            let l = ((r as u16 + g as u16 + b as u16) / 12) as u8;
+            Argb::compose([0xFF, l, l, l])
+        })
+        .collect();
+
+    (bad, Image::new(w, h, data))
+}
+
+fn save(image: &Image, path: &Path) -> Option<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .inspect_err(|e| {
+                let dir = dir.display();
+                error!("Failed to create {}: {}", dir, e)
+            })
+            .ok()?;
+    }
+
+    image.save(path)
+}
+
+/// Renders every visualizer at a few sizes and compares frames against
+/// the reference images, or writes them with `--update`. Failures leave
+/// the actual frame and a diff image in `<dir>/failures/`.
+pub fn golden_main(args: &[String]) {
+    let props = GoldenProps::parse(args);
+
+    let names: Vec<&str> = VisList::new()
+        .names()
+        .into_iter()
+        .filter(|n| {
+            props
+                .only
+                .as_ref()
+                .is_none_or(|only| n.eq_ignore_ascii_case(only))
+        })
+        .collect();
+
+    let failures = props.dir.join("failures");
+    let _ = std::fs::remove_dir_all(&failures);
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+
+    for name in names {
+        for (size, scale) in CASES {
+            let frames = render_case(name, size, scale);
+            let case = case_name(name, size, scale);
+
+            // A visualizer that does not move would pass whatever it reacts to.
+            for (pair, frame) in frames.windows(2).zip(&CHECKPOINTS[1..]) {
+                if compare(&pair[0], &pair[1], props.tolerance).0 == 0 {
+                    failed += 1;
+                    error!("{}: frame {} looks the same as the one before", case, frame);
+                }
+            }
+
+            for (frame, actual) in CHECKPOINTS.iter().zip(&frames) {
+                let file = format!("{case}_{frame:03}.png");
+                let path = props.dir.join(&file);
+
+                if props.update {
+                    save(actual, &path);
+                    continue;
+                }
+
+                let Some(expected) = Image::load(&path) else {
+                    missing += 1;
+                    continue;
+                };
+
+                let bad = if expected.size() == actual.size() {
+                    let (bad, diff) = compare(&expected, actual, props.tolerance);
+
+                    if bad as f32 > MAX_BAD_PIXELS * (expected.width() * expected.height()) as f32 {
+                        save(&diff, &failures.join(file.replace(".png", ".diff.png")));
+                        Some(bad)
+                    } else {
+                        None
+                    }
+                } else {
+                    Some(actual.width() * actual.height())
+                };
+
+                match bad {
+                    Some(bad) => {
+                        failed += 1;
+                        save(actual, &failures.join(&file));
+                        error!("{}: {} pixels differ", file, bad);
+                    }
+
+                    None => passed += 1,
+                }
+            }
+        }
+    }
+
+    let dir = props.dir.display();
+
+    if props.update {
+        info!("Updated reference images in {}", dir);
+        return;
+    }
+
+    info!(
+        "{} passed, {} failed, {} without reference images in {}",
+        passed, failed, missing, dir
+    );
+
+    if failed > 0 || missing > 0 {
+        std::process::exit(1);
+    }
+}
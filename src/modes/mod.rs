@@ -1,4 +1,8 @@
 pub mod console_mode;
+pub mod golden_mode;
+pub mod probe;
+pub mod raw_mode;
+pub mod render_mode;
 pub mod windowed_mode;
 
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -6,7 +10,19 @@ pub enum Mode {
     Win,
     ConAscii,
     ConBlock,
+    ConHalf,
     ConBrail,
+
+    /// Unicode legacy computing blocks, 2x3 and 2x4.
+    ConSextant,
+    ConOctant,
+
+    /// Real pixels, in terminals that support them.
+    ConSixel,
+    ConKitty,
+
+    /// Offline, into image files or a video stream.
+    Render,
 }
 
 impl Mode {
@@ -15,20 +31,90 @@ impl Mode {
             Mode::ConBrail => "braille",
             Mode::ConAscii => "ascii",
             Mode::ConBlock => "block",
+            Mode::ConHalf => "half",
+            Mode::ConSextant => "sextant",
+            Mode::ConOctant => "octant",
+            Mode::ConSixel => "sixel",
+            Mode::ConKitty => "kitty",
             _ => unreachable!(),
         }
     }
 
+    /// The next terminal renderer, skipping graphics
+    /// protocols the terminal does not seem to support.
     pub fn next(self) -> Self {
-        match self {
+        let next = match self {
             Mode::ConAscii => Mode::ConBlock,
-            Mode::ConBlock => Mode::ConBrail,
-            Mode::ConBrail => Mode::ConAscii,
-            _ => self,
+            Mode::ConBlock => Mode::ConHalf,
+            Mode::ConHalf => Mode::ConBrail,
+            Mode::ConBrail => Mode::ConSextant,
+            Mode::ConSextant => Mode::ConOctant,
+            Mode::ConOctant => Mode::ConSixel,
+            Mode::ConSixel => Mode::ConKitty,
+            Mode::ConKitty => Mode::ConAscii,
+            _ => return self,
+        };
+
+        if next.is_supported() {
+            next
+        } else {
+            next.next()
         }
     }
 
     pub fn is_con(&self) -> bool {
-        matches!(self, Mode::ConAscii | Mode::ConBlock | Mode::ConBrail)
+        matches!(
+            self,
+            Mode::ConAscii
+                | Mode::ConBlock
+                | Mode::ConHalf
+                | Mode::ConBrail
+                | Mode::ConSextant
+                | Mode::ConOctant
+                | Mode::ConSixel
+                | Mode::ConKitty
+        )
+    }
+
+    /// Whether the terminal is sent pixels instead of characters.
+    pub fn is_pixels(&self) -> bool {
+        matches!(self, Mode::ConSixel | Mode::ConKitty)
+    }
+
+    /// Asks the terminal, see `probe`. Terminals that do not
+    /// answer are guessed from the environment instead.
+    pub fn is_supported(&self) -> bool {
+        if !self.is_pixels() {
+            return true;
+        }
+
+        if let Some(g) = probe::graphics() {
+            return match self {
+                Mode::ConKitty => g.kitty,
+                _ => g.sixel,
+            };
+        }
+
+        let var = |name| std::env::var(name).unwrap_or_default();
+        let term = var("TERM");
+        let program = var("TERM_PROGRAM");
+
+        match self {
+            Mode::ConKitty => {
+                std::env::var_os("KITTY_WINDOW_ID").is_some()
+                    || term.contains("kitty")
+                    || term.contains("ghostty")
+                    || matches!(program.as_str(), "WezTerm" | "ghostty")
+            }
+
+            Mode::ConSixel => {
+                ["foot", "mlterm", "yaft", "contour"]
+                    .iter()
+                    .any(|t| term.starts_with(t))
+                    || matches!(program.as_str(), "WezTerm" | "iTerm.app" | "mintty")
+            }
+
+            _ => true,
+        }
     }
 }
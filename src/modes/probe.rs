@@ -0,0 +1,143 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// How long to wait for the terminal to answer. Local terminals
+/// take a few milliseconds, this leaves room for ssh.
+const TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Graphics protocols the terminal says it speaks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Graphics {
+    pub sixel: bool,
+    pub kitty: bool,
+}
+
+static ANSWER: OnceLock<Option<Graphics>> = OnceLock::new();
+
+/// What the terminal answered, `None` when it could not be asked or
+/// did not answer in time. It is only asked once, and that has to
+/// happen before crossterm starts reading input, which would take
+/// the answer for key presses.
+pub fn graphics() -> Option<Graphics> {
+    *ANSWER.get_or_init(query)
+}
+
+/// Reads the answers to the queries sent by `query`, once the
+/// primary device attributes (DA1) are in. Sixel is attribute 4.
+fn parse(reply: &[u8]) -> Option<Graphics> {
+    let reply = String::from_utf8_lossy(reply);
+
+    let start = reply.find("\x1b[?")?;
+    let end = start + reply[start..].find('c')?;
+
+    Some(Graphics {
+        sixel: reply[start + 3..end].split(';').any(|a| a == "4"),
+        kitty: reply[..start].contains("\x1b_Gi=31;OK"),
+    })
+}
+
+#[cfg(unix)]
+fn query() -> Option<Graphics> {
+    use std::io::{IsTerminal, Write};
+    use std::os::fd::AsFd;
+    use std::time::Instant;
+
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode, is_raw_mode_enabled};
+    use rustix::event::{PollFd, PollFlags, Timespec, poll};
+
+    let (stdin, mut stdout) = (std::io::stdin(), std::io::stdout());
+
+    if !stdin.is_terminal() || !stdout.is_terminal() {
+        return None;
+    }
+
+    // Otherwise the answer would be echoed and wait for a newline.
+    let was_raw = is_raw_mode_enabled().ok()?;
+    if !was_raw {
+        enable_raw_mode().ok()?;
+    }
+
+    // Kitty answers a query for a 1x1 image, other terminals ignore it.
+    // Every terminal answers DA1, which marks the end of the answers.
+    let _ = write!(stdout, "\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\\x1b[c");
+    let _ = stdout.flush();
+
+    let deadline = Instant::now() + TIMEOUT;
+    let mut reply = Vec::new();
+    let mut chunk = [0u8; 256];
+
+    let answer = loop {
+        if let Some(g) = parse(&reply) {
+            break Some(g);
+        }
+
+        let Ok(left) = Timespec::try_from(deadline.saturating_duration_since(Instant::now()))
+        else {
+            break None;
+        };
+
+        let mut fds = [PollFd::new(&stdin, PollFlags::IN)];
+
+        match poll(&mut fds, Some(&left)) {
+            Ok(0) | Err(_) => break None,
+            _ => {}
+        }
+
+        // Read the descriptor directly, the buffer of `Stdin`
+        // would keep whatever comes after from crossterm.
+        match rustix::io::read(stdin.as_fd(), &mut chunk) {
+            Ok(0) | Err(_) => break None,
+            Ok(n) => reply.extend_from_slice(&chunk[..n]),
+        }
+    };
+
+    if !was_raw {
+        let _ = disable_raw_mode();
+    }
+
+    answer
+}
+
+#[cfg(not(unix))]
+fn query() -> Option<Graphics> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        // Nothing complete yet.
+        assert_eq!(parse(b""), None);
+        assert_eq!(parse(b"\x1b_Gi=31;OK\x1b\\\x1b[?62;4"), None);
+
+        // xterm with sixel support.
+        assert_eq!(
+            parse(b"\x1b[?63;1;2;4;6;9;15;22c"),
+            Some(Graphics {
+                sixel: true,
+                kitty: false
+            })
+        );
+
+        // kitty.
+        assert_eq!(
+            parse(b"\x1b_Gi=31;OK\x1b\\\x1b[?62;c"),
+            Some(Graphics {
+                sixel: false,
+                kitty: true
+            })
+        );
+
+        // An error for the image query is still no.
+        assert_eq!(
+            parse(b"\x1b_Gi=31;EINVAL:bad\x1b\\\x1b[?64;44c"),
+            Some(Graphics {
+                sixel: false,
+                kitty: false
+            })
+        );
+    }
+}
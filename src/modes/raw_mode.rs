@@ -0,0 +1,237 @@
+use std::fs::OpenOptions;
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::time::{Duration, Instant};
+
+use cpal::traits::StreamTrait;
+
+use crate::audio::{get_buf, get_source};
+use crate::data::log::{error, info};
+use crate::math::fast::cubed_sqrt;
+use crate::visualizers::classic::bars::{Bars, MAX_BARS, prepare};
+
+const DEFAULT_BANDS: usize = 16;
+const DEFAULT_FPS: f32 = 30.0;
+
+/// Bar heights from lowest to highest, as waybar and friends show them.
+const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RawFormat {
+    /// Decimal numbers separated by `;`, one frame per line.
+    Ascii,
+    U8,
+    /// Little endian.
+    U16,
+    /// One JSON array per line.
+    Json,
+    /// One line of block characters per frame.
+    Blocks,
+}
+
+impl RawFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "ascii" => Self::Ascii,
+            "u8" => Self::U8,
+            "u16" => Self::U16,
+            "json" => Self::Json,
+            "blocks" => Self::Blocks,
+            _ => return None,
+        })
+    }
+
+    fn default_range(self) -> u32 {
+        match self {
+            Self::U8 => u8::MAX as u32,
+            Self::U16 => u16::MAX as u32,
+            Self::Blocks => BLOCKS.len() as u32 - 1,
+            _ => 1000,
+        }
+    }
+
+    /// Appends one frame of values, each in `0..=range`.
+    fn encode(self, values: &[u32], out: &mut Vec<u8>) {
+        match self {
+            Self::Ascii | Self::Json => {
+                let list = values
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(if self == Self::Json { "," } else { ";" });
+
+                if self == Self::Json {
+                    out.extend_from_slice(format!("[{list}]\n").as_bytes());
+                } else {
+                    out.extend_from_slice(format!("{list}\n").as_bytes());
+                }
+            }
+
+            Self::U8 => out.extend(values.iter().map(|&v| v as u8)),
+
+            Self::U16 => values
+                .iter()
+                .for_each(|&v| out.extend_from_slice(&(v as u16).to_le_bytes())),
+
+            Self::Blocks => {
+                let line: String = values.iter().map(|&v| BLOCKS[v as usize]).collect();
+                out.extend_from_slice(format!("{line}\n").as_bytes());
+            }
+        }
+    }
+}
+
+struct RawProps {
+    format: RawFormat,
+    bands: usize,
+    range: Option<u32>,
+    fps: f32,
+    output: Option<String>,
+}
+
+impl RawProps {
+    fn parse(args: &[String]) -> Self {
+        let mut props = Self {
+            format: RawFormat::Ascii,
+            bands: DEFAULT_BANDS,
+            range: None,
+            fps: DEFAULT_FPS,
+            output: None,
+        };
+
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--format" => {
+                    let format = args
+                        .next()
+                        .expect("Argument error: Expected one of ascii, u8, u16, json, blocks.");
+
+                    props.format = RawFormat::parse(format).expect("Invalid value for format.");
+                }
+
+                "--bands" => {
+                    props.bands = args
+                        .next()
+                        .expect("Argument error: Expected number of bands.")
+                        .parse::<usize>()
+                        .expect("Argument error: Invalid value.");
+
+                    if !(1..=MAX_BARS).contains(&props.bands) {
+                        panic!("Argument error: Number of bands must be from 1 to {MAX_BARS}.");
+                    }
+                }
+
+                "--range" => {
+                    let range = args
+                        .next()
+                        .expect("Argument error: Expected largest value.")
+                        .parse::<u32>()
+                        .expect("Argument error: Invalid value.");
+
+                    props.range = Some(range);
+                }
+
+                "--fps" => {
+                    props.fps = args
+                        .next()
+                        .expect("Argument error: Expected value for refresh rate.")
+                        .parse::<f32>()
+                        .expect("Argument error: Invalid value.");
+
+                    if props.fps <= 0.0 {
+                        panic!("Argument error: fps needs to be larger than 0.");
+                    }
+                }
+
+                "--out" => {
+                    let path = args
+                        .next()
+                        .expect("Argument error: Expected output file or FIFO.");
+
+                    props.output = Some(path.clone()).filter(|p| p != "-");
+                }
+
+                _ => panic!("Argument error: Unknown option {arg} for raw."),
+            }
+        }
+
+        props
+    }
+
+    /// The largest value, within what the format can hold.
+    fn range(&self) -> u32 {
+        let max = self.format.default_range();
+        self.range.map_or(max, |r| r.min(max))
+    }
+}
+
+fn open(output: Option<&str>) -> Option<Box<dyn Write>> {
+    let Some(path) = output else {
+        return Some(Box::new(io::stdout().lock()));
+    };
+
+    // Opening a FIFO waits until something reads from it.
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .inspect_err(|e| error!("Failed to open {}: {}", path, e))
+        .ok()?;
+
+    Some(Box::new(BufWriter::new(file)))
+}
+
+/// Writes the bands of the Bars visualizer every frame instead
+/// of drawing anything, like the raw output of cava.
+pub fn raw_main(args: &[String]) {
+    let props = RawProps::parse(args);
+
+    // Anything else on standard output would garble the values.
+    if props.output.is_none() {
+        crate::data::log::set_log_enabled(false);
+    }
+
+    let Some(mut out) = open(props.output.as_deref()) else {
+        return;
+    };
+
+    let stream = get_source();
+    stream.play().unwrap();
+
+    let range = props.range();
+    let interval = Duration::from_secs_f32(1.0 / props.fps);
+
+    let mut bars = Bars::default();
+    let mut frame = Vec::new();
+    let mut next = Instant::now();
+
+    loop {
+        prepare(&mut get_buf(), props.bands, &mut bars.data, &mut bars.dct);
+
+        // Shaped the way Bars draws them. The first band is the DC offset.
+        let values: Vec<u32> = bars.data[1..=props.bands]
+            .iter()
+            .map(|&v| (cubed_sqrt(v.clamp(0.0, 1.0)) * range as f32).round() as u32)
+            .collect();
+
+        frame.clear();
+        props.format.encode(&values, &mut frame);
+
+        if let Err(e) = out.write_all(&frame).and_then(|_| out.flush()) {
+            // Whatever read the values went away.
+            if e.kind() != ErrorKind::BrokenPipe {
+                error!("Failed to write values: {}", e);
+            }
+            break;
+        }
+
+        next += interval;
+        std::thread::sleep(next.saturating_duration_since(Instant::now()));
+    }
+
+    stream.pause().unwrap();
+
+    info!("Bye!");
+}
@@ -0,0 +1,217 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use crate::audio::{get_buf, wav::Wav};
+use crate::data::{
+    Program,
+    log::{error, info},
+};
+use crate::graphics::{Painter, Pixel, RenderEffect, image::Image};
+use crate::modes::Mode;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameFormat {
+    Png,
+    Qoi,
+    Y4m,
+}
+
+impl FrameFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "png" => Some(Self::Png),
+            "qoi" => Some(Self::Qoi),
+            "y4m" => Some(Self::Y4m),
+            _ => None,
+        }
+    }
+
+    /// Y4M for `-` (standard output) and `.y4m` files, PNG frames otherwise.
+    fn guess(output: &str) -> Self {
+        if output == "-" || output.ends_with(".y4m") {
+            Self::Y4m
+        } else {
+            Self::Png
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RenderProps {
+    pub input: Option<String>,
+    pub output: Option<String>,
+    pub format: Option<FrameFormat>,
+}
+
+enum Sink {
+    Frames {
+        dir: PathBuf,
+        extension: &'static str,
+    },
+    Y4m(Box<dyn Write>),
+}
+
+impl Sink {
+    fn open(output: &str, format: FrameFormat) -> Option<Self> {
+        let extension = match format {
+            FrameFormat::Png => "png",
+            FrameFormat::Qoi => "qoi",
+
+            FrameFormat::Y4m => {
+                let out: Box<dyn Write> = if output == "-" {
+                    Box::new(io::stdout().lock())
+                } else {
+                    let file = File::create(output)
+                        .inspect_err(|e| error!("Failed to create {}: {}", output, e))
+                        .ok()?;
+
+                    Box::new(file)
+                };
+
+                return Some(Self::Y4m(Box::new(BufWriter::new(out))));
+            }
+        };
+
+        std::fs::create_dir_all(output)
+            .inspect_err(|e| error!("Failed to create {}: {}", output, e))
+            .ok()?;
+
+        Some(Self::Frames {
+            dir: PathBuf::from(output),
+            extension,
+        })
+    }
+
+    fn write(&mut self, index: u64, image: &Image, milli_hz: u32) -> Option<()> {
+        match self {
+            Self::Frames { dir, extension } => {
+                image.save(dir.join(format!("frame_{index:06}.{extension}")))
+            }
+
+            Self::Y4m(out) => write_y4m(out, index, image, milli_hz)
+                .inspect_err(|e| error!("Failed to write Y4M frame: {}", e))
+                .ok(),
+        }
+    }
+}
+
+/// Writes the header before the first frame, then each frame
+/// as full resolution (4:4:4) BT.601 limited range YUV.
+fn write_y4m(out: &mut dyn Write, index: u64, image: &Image, milli_hz: u32) -> io::Result<()> {
+    let (w, h) = (image.width(), image.height());
+
+    if index == 0 {
+        writeln!(out, "YUV4MPEG2 W{w} H{h} F{milli_hz}:1000 Ip A1:1 C444")?;
+    }
+
+    let mut planes = vec![0u8; w * h * 3];
+    let (y_plane, uv) = planes.split_at_mut(w * h);
+    let (u_plane, v_plane) = uv.split_at_mut(w * h);
+
+    for i in 0..w * h {
+        let [_, r, g, b] = image.pixel(i % w, i / w).decompose();
+        let (r, g, b) = (r as i32, g as i32, b as i32);
+
+        y_plane[i] = (((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8;
+        u_plane[i] = (((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128) as u8;
+        v_plane[i] = (((112 * r - 94 * g - 18 * b + 128) >> 8) + 128) as u8;
+    }
+
+    out.write_all(b"FRAME\n")?;
+    out.write_all(&planes)?;
+    out.flush()
+}
+
+/// Renders the visualizer over a WAV file, one frame every `1/fps`
+/// seconds of audio, without opening a window or touching the terminal.
+/// The program as `coffeevis render` sets it up from `args`,
+/// which come after the subcommand.
+pub fn program(args: &[String]) -> Program {
+    let mut prog = Program::new();
+    prog.set_mode(Mode::Render);
+    prog.eval_args(&mut args.iter())
+}
+
+pub fn render_main(args: &[String]) {
+    let mut prog = program(args);
+
+    let input = prog
+        .render_props
+        .input
+        .clone()
+        .expect("Argument error: Expected an audio file with --input.");
+
+    let output = prog
+        .render_props
+        .output
+        .clone()
+        .expect("Argument error: Expected an output path with --out.");
+
+    let format = prog
+        .render_props
+        .format
+        .unwrap_or_else(|| FrameFormat::guess(&output));
+
+    if output == "-" {
+        crate::data::log::set_log_enabled(false);
+    }
+
+    let milli_hz = prog.milli_hz();
+
+    if milli_hz == 0 {
+        panic!("Argument error: fps needs to be larger than 0.");
+    }
+
+    let Some(wav) = Wav::load(&input) else {
+        return;
+    };
+
+    let Some(mut sink) = Sink::open(&output, format) else {
+        return;
+    };
+
+    // Visualizers that seed from the clock must not differ between runs.
+    crate::math::rng::fix_clock(0);
+
+    let scale = prog.scale();
+    let width = prog.window_props.width as usize * scale as usize;
+    let height = prog.window_props.height as usize * scale as usize;
+
+    // Interlacing only makes sense when presenting to a screen.
+    let fill = prog.get_win_render_effect() != RenderEffect::Crt;
+
+    let mut buffer = vec![0; width * height];
+
+    let rate = wav.sample_rate as u64 * 1000;
+    let total = wav.frame_count() as u64;
+    let frames = (total * milli_hz as u64).div_ceil(rate);
+
+    info!(
+        "Rendering {} frames of {}x{} from {}",
+        frames, width, height, input
+    );
+
+    for index in 0..frames {
+        let start = (index * rate / milli_hz as u64) as usize;
+        let end = (((index + 1) * rate / milli_hz as u64).min(total)) as usize;
+
+        let mut audiobuf = get_buf();
+
+        if end > start {
+            audiobuf.read_from_input(&wav.samples[start * 2..end * 2]);
+        }
+
+        let mut pix = Painter::from(&mut buffer, width, height, scale, 0, fill);
+        prog.render(&mut pix, &mut audiobuf);
+
+        let image = pix.to_image();
+        drop(audiobuf);
+
+        if sink.write(index, &image, milli_hz).is_none() {
+            return;
+        }
+    }
+
+    info!("Rendered {} frames to {}", frames, output);
+}
@@ -71,6 +71,9 @@ struct WindowState {
     pub renderer: Option<Renderer>,
     pub final_buffer_size: PhysicalSize<u32>,
     pub last_draw: Instant,
+
+    /// The frame at the logical size, when upscaling with a filter.
+    pub logical: Vec<u32>,
 }
 
 impl ApplicationHandler for WindowState {
@@ -226,16 +229,30 @@ impl ApplicationHandler for WindowState {
                     _ => {}
                 }
 
-                let mut pix = Painter::from(
-                    &mut buffer,
-                    self.final_buffer_size.width as usize,
-                    self.final_buffer_size.height as usize,
-                    2,
-                    *field,
-                    fill,
-                );
+                let scale = self.prog.scale();
+                let width = self.final_buffer_size.width as usize;
+                let height = self.final_buffer_size.height as usize;
+
+                if let Some(upscale) = self.prog.get_upscale() {
+                    let s = scale as usize;
+                    let (w, h) = (width / s, height / s);
+                    self.logical.resize(w * h, 0);
+
+                    let mut pix = Painter::from(&mut self.logical, w, h, 1, 0, true);
+                    self.prog.render(&mut pix, &mut audiobuf);
+                    let background = pix.theme().background;
 
-                self.prog.render(&mut pix, &mut audiobuf);
+                    upscale.upscale(&self.logical, w, h, &mut buffer, width, s);
+
+                    // What is left of a window that is not a multiple of the scale.
+                    for (y, row) in buffer.chunks_exact_mut(width).enumerate() {
+                        let x = if y < h * s { w * s } else { 0 };
+                        row[x..].fill(background);
+                    }
+                } else {
+                    let mut pix = Painter::from(&mut buffer, width, height, scale, *field, fill);
+                    self.prog.render(&mut pix, &mut audiobuf);
+                }
 
                 window.pre_present_notify();
 
@@ -323,6 +340,12 @@ impl ApplicationHandler for WindowState {
                         Key::Character("n") => self.prog.change_visualizer(true),
                         Key::Character("b") => self.prog.change_visualizer(false),
                         Key::Character("\\") => self.prog.toggle_auto_switch(),
+                        Key::Named(NamedKey::Tab) => self.prog.cycle_focus(),
+                        Key::Character("t") => self.prog.cycle_theme(),
+                        Key::Character("p") => self.prog.screenshot(),
+                        Key::Character("g") => self.prog.toggle_recording(),
+                        Key::Character("f") => self.prog.toggle_fps(),
+                        Key::Character("h") => self.prog.toggle_hints(),
                         Key::Character("/") => self.prog.reset_parameters(),
                         _ => {}
                     }
@@ -371,6 +394,7 @@ pub fn winit_main(prog: Program) {
             renderer: None,
             final_buffer_size: PhysicalSize::<u32>::new(0, 0),
             last_draw: Instant::now(),
+            logical: Vec::new(),
         })
         .unwrap();
 }
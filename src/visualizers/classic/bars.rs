@@ -4,7 +4,7 @@ use crate::math::Dct;
 use crate::math::{self, Cplx, interpolate::linearf};
 use crate::visualizers::{Visualizer, VisualizerArgs};
 
-const MAX_BARS: usize = 144;
+pub const MAX_BARS: usize = 144;
 const MAX_BARS1: usize = MAX_BARS + 1;
 
 pub struct Bars {
@@ -17,7 +17,14 @@ pub struct BarsCircle {
     pub dct: Dct<f32>,
 }
 
-fn prepare(stream: &mut crate::AudioBuffer, bar_num: usize, data: &mut [f32], dct: &mut Dct<f32>) {
+/// Fills `data[..=bar_num]` with smoothed band levels from 0 to 1,
+/// `data[0]` being the DC offset.
+pub fn prepare(
+    stream: &mut crate::AudioBuffer,
+    bar_num: usize,
+    data: &mut [f32],
+    dct: &mut Dct<f32>,
+) {
     let bar_num = bar_num + 1;
 
     let bnf = bar_num as f32;
@@ -118,7 +125,10 @@ impl Visualizer for Bars {
             let peak = (bar * 255 / pix.logical_height()) as u8;
             let _red = (fade.wrapping_mul(2) / 3).saturating_add(128).max(peak);
 
-            pix.color(u32::from_be_bytes([0xFF, 0xFF, (fade).max(peak), 0]));
+            let green = fade.max(peak);
+            let native = u32::from_be_bytes([0xFF, 0xFF, green, 0]);
+
+            pix.color(pix.theme().ramp(green as f32 / 255.0, native));
             pix.rect(
                 P2(
                     (size.0 * idx / bar_num) as i32,
@@ -170,8 +180,7 @@ impl Visualizer for BarsCircle {
         let bnf = bar_num as f32;
         let bnf_recip = 1.0 / bnf;
 
-        let wh = pix.logical_width() as i32 / 2;
-        let hh = pix.logical_height() as i32 / 2;
+        let center = pix.logical_size().to_cplx().center();
 
         prepare(stream, bar_num, &mut self.data, &mut self.dct);
 
@@ -191,14 +200,8 @@ impl Visualizer for BarsCircle {
             let bar = linearf(self.data[i], self.data[i_next], t) * sizef;
             let bar = bar * 0.7;
 
-            let p1 = P2(
-                wh + (sizef * angle.0) as i32 / 2,
-                hh + (sizef * angle.1) as i32 / 2,
-            );
-            let p2 = P2(
-                wh + ((sizef - bar) * angle.0) as i32 / 2,
-                hh + ((sizef - bar) * angle.1) as i32 / 2,
-            );
+            let p1 = center + angle * (sizef * 0.5);
+            let p2 = center + angle * ((sizef - bar) * 0.5);
 
             let pulse = (stream.get(i * 3 / 2).0 * 32768.0) as u8;
             let peak = (bar as i32 * 255 / size).min(255) as u8;
@@ -208,8 +211,9 @@ impl Visualizer for BarsCircle {
             let b: u8 = 0xFF;
             let c = u32::compose([0xFF, r, g, b]);
 
-            pix.color(c);
-            pix.line(p1, p2);
+            pix.color(pix.theme().ramp(g as f32 / 255.0, c));
+            pix.mixerm();
+            pix.line_aa(p1, p2);
         }
     }
 }
@@ -67,9 +67,9 @@ impl Visualizer for Lazer {
 
         pix.fade(3);
 
-        pix.color(color);
-        pix.mixerd();
-        pix.line(p1.to_p2(), p0.to_p2());
+        pix.color(pix.theme().ramp(p0.0 / w, color));
+        pix.mixerm();
+        pix.line_aa(p1, p0);
 
         p1 = p0;
 
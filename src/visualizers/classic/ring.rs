@@ -40,12 +40,14 @@ impl Visualizer for Ring {
 
             let int = (smp.l1_norm() * 128.0) as u8;
 
-            pix.color(u32::from_be_bytes([
+            let native = u32::from_be_bytes([
                 255,
                 ((128 + x.abs() * 64 / size) as u8).saturating_sub(int),
                 255,
                 ((128 + y.abs() * 64 / size) as u8).saturating_add(int),
-            ]));
+            ]);
+
+            pix.color(pix.theme().ramp(int as f32 / 128.0, native));
             pix.mixerd();
             pix.plot(P2(x / 2 + width_top_h, y / 2 + height_top_h));
         }
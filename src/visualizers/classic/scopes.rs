@@ -57,19 +57,26 @@ impl Visualizer for Vectorscope {
             di += INCREMENT;
         }
 
+        let center = Cplx(width_top_h as f32, height_top_h as f32);
+        let mut prev: Option<Cplx> = None;
+
         while di < DEFAULT_WAV_WIN {
             let sample = get_cplx(di);
 
             let sample = smoothed_sample.update(sample);
 
-            let x = (sample.0 * scale) as i32;
-            let y = (sample.1 * scale) as i32;
-            let amp = (x.abs() + y.abs()) * 3 / 2;
+            let p = sample * scale;
+            let amp = ((p.0.abs() + p.1.abs()) * 1.5) as i32;
+            let p = p + center;
+
+            let red = to_color(amp, sizei);
+            let native = u32::from_be_bytes([255, red, 255, 64]);
 
-            pix.color(u32::from_be_bytes([255, to_color(amp, sizei), 255, 64]));
-            pix.mixerd();
-            pix.plot(P2(x + width_top_h, y + height_top_h));
+            pix.color(pix.theme().ramp(red as f32 / 255.0, native));
+            pix.mixerm();
+            pix.line_aa(prev.unwrap_or(p), p);
 
+            prev = Some(p);
             di += INCREMENT;
         }
 
@@ -83,7 +90,7 @@ impl Vectorscope {
     pub fn draw_cross(&mut self, pix: &mut crate::graphics::Painter) {
         let P2(width, height) = pix.logical_size();
 
-        pix.color(CROSS_COL);
+        pix.color(pix.theme().muted(CROSS_COL));
         pix.mixer(u32::over);
 
         if self.cross {
@@ -216,10 +223,12 @@ impl Visualizer for Oscilloscope {
             rmin = rmin * scale + center;
             rmax = rmax * scale + center;
 
+            let theme = pix.theme();
+
             pix.mixer(u32::or);
-            pix.color(u32::compose([255, 0, 55, 255]));
+            pix.color(theme.accent(0, u32::compose([255, 0, 55, 255])));
             pix.rect(P2(x as i32, lmin as i32), 1, (lmax - lmin) as usize);
-            pix.color(u32::compose([255, 0, 255, 55]));
+            pix.color(theme.accent(1, u32::compose([255, 0, 255, 55])));
             pix.rect(P2(x as i32, rmin as i32), 1, (rmax - rmin) as usize);
         }
 
@@ -76,7 +76,9 @@ impl Visualizer for Shaky {
         let blue = 255i32.saturating_sub(final_y.abs() * 5) as u8;
         let green = (amplitude * 0.0001) as u8;
 
-        pix.color(u32::compose([0xFF, red, green, blue]));
+        let native = u32::compose([0xFF, red, green, blue]);
+
+        pix.color(pix.theme().ramp(red as f32 / 255.0, native));
         pix.mixerm();
         pix.rect(
             crate::graphics::P2(final_x + width / 2 - 1, final_y + height / 2 - 1),
@@ -1,7 +1,7 @@
-use crate::graphics::{P2, Pixel};
+use crate::graphics::Pixel;
 use crate::math::{Cplx, cos_sin, interpolate::linearf};
 use crate::visualizers::{Visualizer, VisualizerArgs};
-use std::f32::consts::{PI, TAU};
+use std::f32::consts::TAU;
 
 #[derive(Default)]
 pub struct Slice {
@@ -60,28 +60,16 @@ impl Visualizer for Slice {
 
         let new_angle = mt.0 + amp;
 
-        let d = 1.0 / (big_radius_f * PI);
-
         let channel = high as u8 / 2;
         let color = u32::compose([0xFF, channel, channel, channel]);
 
-        let mut o = mt.0;
-        while o < new_angle {
-            let x = o.cos() * big_radius_f;
-            let y = o.sin() * big_radius_f;
-
-            let p = P2(center.0 + x as i32, center.1 + y as i32);
-
-            pix.color(color);
-            pix.mixer(blend);
-            pix.line(center, p);
-
-            o += d;
-        }
+        pix.color(pix.theme().ramp(channel as f32 / 127.0, color));
+        pix.mixer(blend);
+        pix.pie(center.to_cplx(), big_radius_f, mt.0, new_angle);
 
         mt.0 = new_angle % TAU;
 
-        pix.color(0xFF_FF_FF_FF);
+        pix.color(pix.theme().foreground(0xFF_FF_FF_FF));
         pix.mixerd();
         pix.circle(center, small_radius, true);
 
@@ -1,4 +1,4 @@
-use crate::graphics::P2;
+use crate::graphics::{P2, blend::BlendMode, gradient::Gradient};
 use crate::math::Dct;
 use crate::math::{Cplx, interpolate::*};
 use crate::visualizers::Visualizer;
@@ -73,42 +73,71 @@ impl Visualizer for Spectrum {
         pix.clear();
         pix.mixerd();
 
-        for y in 0..h {
-            let ifrac = (y as f32 / hf).exp2() - 1.0f32;
-            let ifloat = ifrac * RANGEF;
-            let ifloor = ifloat as usize;
-            let iceil = ifloat.ceil() as usize;
-            let ti = ifloat.fract();
+        let theme = pix.theme();
 
-            let sfloor = self.buffer[ifloor];
-            let sceil = self.buffer[iceil];
+        // Left and right widths of every row, from the bottom up.
+        let rows: Vec<(f32, f32)> = (0..h)
+            .map(|y| {
+                let ifrac = (y as f32 / hf).exp2() - 1.0f32;
+                let ifloat = ifrac * RANGEF;
+                let ifloor = ifloat as usize;
+                let iceil = ifloat.ceil() as usize;
+                let ti = ifloat.fract();
 
-            let sl = smooth_step(sfloor.0, sceil.0, ti);
-            let sr = smooth_step(sfloor.1, sceil.1, ti);
+                let sfloor = self.buffer[ifloor];
+                let sceil = self.buffer[iceil];
 
-            let sl = sl.powf(1.3) * whf;
-            let sr = sr.powf(1.3) * whf;
+                let sl = smooth_step(sfloor.0, sceil.0, ti);
+                let sr = smooth_step(sfloor.1, sceil.1, ti);
 
-            let channel = (y * 255 / h) as u8;
-            let green = 255.min(16 + (3.0f32 * (sl + sr)) as u32) as u8;
+                (sl.powf(1.3) * whf, sr.powf(1.3) * whf)
+            })
+            .collect();
 
-            let color = u32::from_be_bytes([0xFF, 255 - channel, green, 128 + channel / 2]);
+        pix.gradient(theme.gradient(
+            Gradient::linear(P2(0, h), P2(0, 0)),
+            &[
+                (0.0, u32::from_be_bytes([0xFF, 0xFF, 0x10, 0x80])),
+                (1.0, u32::from_be_bytes([0xFF, 0x00, 0x10, 0xFF])),
+            ],
+        ));
 
-            let ry = h - y;
+        for (y, &(sl, sr)) in rows.iter().enumerate() {
+            let ry = h - y as i32;
+
+            pix.rect_xy(P2((whf - sl) as i32, ry), P2(winwh + 1, ry));
+            pix.rect_xy(P2(winwh + 1, ry), P2((whf + sr) as i32, ry));
+        }
 
-            let rect_l = P2((whf - sl) as i32, ry);
-            let rect_r = P2((whf + sr) as i32, ry);
-            let middle = P2(winwh + 1, ry);
+        // Louder rows get greener.
+        pix.blend_mode(BlendMode::Add);
 
-            pix.color(color);
-            pix.rect_xy(rect_l, middle);
-            pix.rect_xy(middle, rect_r);
+        for (y, &(sl, sr)) in rows.iter().enumerate() {
+            let ry = h - y as i32;
+            let green = 239.min((3.0f32 * (sl + sr)) as u32) as u8;
+
+            pix.color(theme.accent(0, u32::from_be_bytes([green, 0x00, 0xFF, 0x00])));
+            pix.rect_xy(P2((whf - sl) as i32, ry), P2(winwh + 1, ry));
+            pix.rect_xy(P2(winwh + 1, ry), P2((whf + sr) as i32, ry));
+        }
+
+        pix.mixerd();
+
+        for y in 0..h {
+            let ry = h - y;
 
             let s = stream.get((h - y) as usize);
             let c1 = if s.0 > 0.0 { 255 } else { 0 };
             let c2 = if s.1 > 0.0 { 255 } else { 0 };
 
-            pix.color(u32::from_be_bytes([255, c1, 0, c2]));
+            let native = u32::from_be_bytes([255, c1, 0, c2]);
+
+            pix.color(match (c1, c2) {
+                (0, 0) => theme.muted(native),
+                (_, 0) => theme.accent(0, native),
+                (0, _) => theme.accent(1, native),
+                _ => theme.foreground(native),
+            });
             pix.rect(P2(winwh - 1, ry), 2, 1);
         }
     }
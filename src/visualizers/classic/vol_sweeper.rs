@@ -27,15 +27,15 @@ impl Visualizer for VolSweeper {
             (sum / (SAMPLE_SIZE / 3) as f32 * pix.logical_width() as f32) as usize
         };
 
-        let color = (w * 255 / pix.logical_width()).min(255) as u8;
+        let level = (w * 255 / pix.logical_width()).min(255) as u8;
         let color = u32::from_be_bytes([
             255,
             255,
-            (sin_norm(color as f32 / 512.0) * 255.0) as u8,
-            color,
+            (sin_norm(level as f32 / 512.0) * 255.0) as u8,
+            level,
         ]);
 
-        pix.color(color);
+        pix.color(pix.theme().ramp(level as f32 / 255.0, color));
         pix.rect(P2(0, self.sweepi as i32), w, 1);
 
         match (self.sweepi >= pix.logical_height(), self.pong) {
@@ -27,7 +27,9 @@ impl Visualizer for Wave {
             let b: u8 = (smp.1 * 144.0 + 128.0) as u8;
             let g: u8 = ((r as u16 + b as u16) / 2) as u8;
 
-            pix.color(u32::from_be_bytes([b, r, g, b]));
+            let native = u32::from_be_bytes([b, r, g, b]);
+
+            pix.color(pix.theme().ramp(g as f32 / 255.0, native));
             pix.mixerm();
             pix.rect(P2(x as i32, 0), 1, height);
         }
@@ -0,0 +1,154 @@
+use crate::graphics::{Argb, Painter, Pixel};
+use crate::math::Cplx;
+
+/// Per pixel displacement. Takes the position in -1..=1 (y scaled
+/// by the aspect ratio) and the time, returns an offset in the same units.
+pub type WarpFn = fn(Cplx, f32) -> Cplx;
+
+/// How the previous frame is moved before the new one is drawn on top.
+#[derive(Clone, Copy)]
+pub struct Motion {
+    /// Above 1 zooms in, pulling the picture outward.
+    pub zoom: f32,
+
+    /// Clockwise, in radians per frame.
+    pub rotation: f32,
+
+    /// In pixels per frame.
+    pub translate: Cplx,
+
+    /// Fraction of the color kept every frame, 0..=255.
+    pub decay: u8,
+
+    pub warp: Option<WarpFn>,
+    pub time: f32,
+}
+
+impl Default for Motion {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            rotation: 0.0,
+            translate: Cplx::zero(),
+            decay: 0xF0,
+            warp: None,
+            time: 0.0,
+        }
+    }
+}
+
+// Sample positions are kept in 24.8 fixed point
+// so the inner loop stays in integers.
+const FRAC_BITS: u32 = 8;
+const ONE: i32 = 1 << FRAC_BITS;
+const FRAC_MASK: i32 = ONE - 1;
+
+fn lerp_channels(a: Argb, b: Argb, t: u32) -> [u32; 3] {
+    let [_, ar, ag, ab] = a.decompose();
+    let [_, br, bg, bb] = b.decompose();
+    let s = ONE as u32 - t;
+
+    [
+        ar as u32 * s + br as u32 * t,
+        ag as u32 * s + bg as u32 * t,
+        ab as u32 * s + bb as u32 * t,
+    ]
+}
+
+/// MilkDrop style feedback: the last frame is zoomed, rotated, moved,
+/// warped and faded, then drawn back under the new content.
+///
+/// Call `warp` in place of `clear` and `capture` once everything
+/// is drawn. Everything happens at logical resolution.
+#[derive(Default)]
+pub struct Feedback {
+    width: usize,
+    height: usize,
+    frame: Vec<Argb>,
+    out: Vec<Argb>,
+}
+
+impl Feedback {
+    pub fn warp(&mut self, pix: &mut Painter, motion: &Motion) {
+        let (w, h) = pix.logical_sizeu();
+        let background = pix.theme().background;
+
+        if (w, h) != (self.width, self.height) || self.frame.len() != w * h {
+            self.width = w;
+            self.height = h;
+            self.frame.clear();
+            self.frame.resize(w * h, background);
+        }
+
+        if w == 0 || h == 0 {
+            return;
+        }
+
+        let center = Cplx(w as f32, h as f32) * 0.5;
+        let norm = 1.0 / center.0.max(center.1);
+
+        // Inverse transform, from the new frame back to the old one.
+        let (sin, cos) = motion.rotation.sin_cos();
+        let inv = 1.0 / motion.zoom.max(f32::EPSILON);
+        let decay = motion.decay as i32;
+        let bg = background.decompose();
+
+        self.out.clear();
+        self.out.reserve(w * h);
+
+        for y in 0..h {
+            for x in 0..w {
+                let d = Cplx(x as f32, y as f32) - center;
+
+                let mut src = Cplx(d.0 * cos + d.1 * sin, d.1 * cos - d.0 * sin) * inv;
+                src = src + center - motion.translate;
+
+                if let Some(warp) = motion.warp {
+                    src = src + warp(d * norm, motion.time) / norm;
+                }
+
+                let sx = (src.0 * ONE as f32) as i32;
+                let sy = (src.1 * ONE as f32) as i32;
+
+                let (x0, y0) = (sx >> FRAC_BITS, sy >> FRAC_BITS);
+
+                if x0 < 0 || y0 < 0 || x0 >= w as i32 || y0 >= h as i32 {
+                    self.out.push(background);
+                    continue;
+                }
+
+                let (fx, fy) = ((sx & FRAC_MASK) as u32, (sy & FRAC_MASK) as u32);
+
+                // The last column and row blend with themselves.
+                let (x0, y0) = (x0 as usize, y0 as usize);
+                let (x1, y1) = ((x0 + 1).min(w - 1), (y0 + 1).min(h - 1));
+                let (top, bottom) = (&self.frame[y0 * w..], &self.frame[y1 * w..]);
+
+                let top = lerp_channels(top[x0], top[x1], fx);
+                let bottom = lerp_channels(bottom[x0], bottom[x1], fx);
+
+                // Both lerps add 8 fractional bits, the decay
+                // then pulls the color toward the background.
+                let channel = |t: u32, b: u32, bg: u8| {
+                    let c = ((t * (ONE as u32 - fy) + b * fy) >> (FRAC_BITS * 2)) as i32;
+                    let bg = bg as i32;
+                    (bg + (((c - bg) * decay) >> 8)) as u8
+                };
+
+                self.out.push(Argb::compose([
+                    0xFF,
+                    channel(top[0], bottom[0], bg[1]),
+                    channel(top[1], bottom[1], bg[2]),
+                    channel(top[2], bottom[2], bg[3]),
+                ]));
+            }
+        }
+
+        pix.write_logical(&self.out);
+    }
+
+    /// Keeps the finished frame for the next call to `warp`.
+    pub fn capture(&mut self, pix: &Painter) {
+        pix.read_logical(&mut self.frame);
+    }
+}
@@ -4,6 +4,8 @@ use crate::{
     math::{self, Cplx},
 };
 
+pub mod feedback;
 pub mod rain;
+pub mod tunnel;
 
 pub fn draw_bubbles(_prog: &mut crate::Program, _stream: &mut crate::AudioBuffer) {}
@@ -3,7 +3,10 @@ use std::cell::RefCell;
 use crate::{
     audio::AudioBuffer,
     data::{DEFAULT_SIZE_WIN, Program},
-    graphics::{Argb, P2, Painter, Pixel},
+    graphics::{
+        Argb, P2, Painter, Pixel,
+        stroke::{LineCap, LineJoin, Stroke},
+    },
     math::{
         Cplx,
         interpolate::linearf,
@@ -63,14 +66,16 @@ impl Thunder {
     }
 
     pub fn draw(&self, canvas: &mut Painter) {
-        self.segments.windows(2).for_each(|pair| {
-            let p1 = pair[0];
-            let p2 = pair[1];
-
-            canvas.color(0xFF_FF_FF_FF.set_alpha(self.fade));
-            canvas.mixer(u32::add);
-            canvas.line(p1, p2);
-        });
+        let points = self
+            .segments
+            .iter()
+            .map(|p| p.to_cplx())
+            .collect::<Vec<_>>();
+
+        canvas.color(canvas.theme().accent(0, 0xFF_FF_FF_FF).set_alpha(self.fade));
+        canvas.mixer(u32::add);
+        canvas.stroke(Stroke::new(1.5).cap(LineCap::Round).join(LineJoin::Round));
+        canvas.polyline(&points);
     }
 
     pub fn fade(&mut self) {
@@ -129,7 +134,7 @@ impl RainDrop {
         while current_length > 0 && p.1 >= 0 {
             let fade = current_length * 255 / self.length;
             let fade = fade as u8;
-            canvas.color(self.color.set_alpha(fade));
+            canvas.color(canvas.theme().foreground(self.color).set_alpha(fade));
             canvas.mixerm();
             canvas.plot(p);
             p.1 -= 1;
@@ -185,12 +190,9 @@ impl Visualizer for Rain {
 
         let blue = 0.7 - vol2 * 0.005;
 
-        pix.color(u32::from_be_bytes([
-            0xFF,
-            0,
-            (119.0 * blue) as u8,
-            (255.0 * blue) as u8,
-        ]));
+        let sky = u32::from_be_bytes([0xFF, 0, (119.0 * blue) as u8, (255.0 * blue) as u8]);
+
+        pix.color(pix.theme().muted(sky));
         pix.fill();
 
         let size = pix.logical_size();
@@ -0,0 +1,99 @@
+use std::f32::consts::TAU;
+
+use crate::{
+    graphics::Pixel,
+    math::{Cplx, interpolate::linearf},
+    visualizers::{
+        Visualizer, VisualizerArgs,
+        milk::feedback::{Feedback, Motion},
+    },
+};
+
+const POINTS: usize = 96;
+
+/// Rotates each ring of the picture by a different amount,
+/// which twists the tunnel into a swirl.
+fn swirl(p: Cplx, t: f32) -> Cplx {
+    let r = p.mag();
+    p.times_i() * (0.015 * (r * 7.0 - t * 2.0).sin())
+}
+
+#[derive(Default)]
+pub struct Tunnel {
+    feedback: Feedback,
+    bass: f32,
+    time: f32,
+}
+
+impl Visualizer for Tunnel {
+    fn name(&self) -> &'static str {
+        "Tunnel"
+    }
+
+    fn perform(&mut self, args: VisualizerArgs) {
+        let VisualizerArgs {
+            pix, stream, delta, ..
+        } = args;
+
+        let bass = {
+            let mut smooth = Cplx::zero();
+            let mut sum = 0.0;
+
+            for i in 0..512 {
+                smooth = smooth + (stream.get(i) - smooth) * 0.05;
+                sum += smooth.l1_norm();
+            }
+
+            sum / 512.0
+        };
+
+        self.bass = linearf(self.bass, bass, 0.3);
+        self.time += delta.clamp(0.0, 0.1) * (1.0 + self.bass * 4.0);
+
+        // Tuned at 60 fps, the motion is per frame.
+        let frames = delta.clamp(0.0, 0.1) * 60.0;
+
+        let motion = Motion {
+            zoom: (1.02 + self.bass * 0.1).powf(frames),
+            rotation: (0.01 + self.bass * 0.03) * frames,
+            decay: (256.0 * (240.0f32 / 256.0).powf(frames)) as u8,
+            warp: Some(swirl),
+            time: self.time,
+            ..Motion::default()
+        };
+
+        self.feedback.warp(pix, &motion);
+
+        let size = pix.logical_size().to_cplx();
+        let center = size * 0.5;
+        let radius = size.0.min(size.1) * (0.08 + self.bass * 0.2);
+
+        let points: Vec<Cplx> = (0..=POINTS)
+            .map(|i| {
+                let t = i % POINTS;
+                let angle = t as f32 / POINTS as f32 * TAU;
+                let r = radius * (1.0 + stream.get(t * 4).0 * 0.8);
+                center + Cplx::euler(angle + self.time) * r
+            })
+            .collect();
+
+        let native = Cplx::euler(self.time * 0.7);
+        let native = u32::compose([
+            0xFF,
+            (128.0 + native.0 * 127.0) as u8,
+            (128.0 + native.1 * 127.0) as u8,
+            0xFF,
+        ]);
+
+        pix.color(pix.theme().ramp((self.bass * 4.0).min(1.0), native));
+        pix.mixerm();
+
+        for seg in points.windows(2) {
+            pix.line_aa(seg[0], seg[1]);
+        }
+
+        self.feedback.capture(pix);
+
+        stream.autoslide();
+    }
+}
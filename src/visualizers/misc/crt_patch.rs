@@ -26,18 +26,20 @@ impl CrtPatch {
 }
 
 impl Visualizer for CrtPatch {
+    // Drawn on the overlay so the sweep does not end up in the picture.
     fn perform(&mut self, args: crate::visualizers::VisualizerArgs) {
-        args.pix.mixerm();
-        args.pix.color(self.color);
-        self.size = args.pix.logical_height() / 3;
-        self.sweep_amount = args.pix.logical_height() / 3;
+        let pix = args.overlay;
+
+        pix.mixerm();
+        pix.color(pix.theme().foreground(self.color));
+        self.size = pix.logical_height() / 3;
+        self.sweep_amount = pix.logical_height() / 3;
 
         for row in (0..self.size).step_by(1) {
-            let row = (self.row_idx + row) % args.pix.logical_height().max(1);
-            args.pix
-                .rect(P2(0, row as i32), args.pix.logical_width(), 1);
+            let row = (self.row_idx + row) % pix.logical_height().max(1);
+            pix.rect(P2(0, row as i32), pix.logical_width(), 1);
         }
 
-        self.row_idx = (self.row_idx + self.sweep_amount) % args.pix.logical_height()
+        self.row_idx = (self.row_idx + self.sweep_amount) % pix.logical_height()
     }
 }
@@ -72,7 +72,7 @@ impl Visualizer for Example {
 
         // Draw
         pix.clear();
-        pix.color(0xFF_FF_FF_FF); // White
+        pix.color(pix.theme().foreground(0xFF_FF_FF_FF)); // White, or the theme's foreground
         pix.circle(pos.to_p2(), 3, true); // radius = 3, filled = true
     }
 }
@@ -1,6 +1,7 @@
 use std::collections::VecDeque;
 
 use crate::graphics::P2;
+use crate::graphics::font::{FONT_4X6, TextAlign};
 use crate::math::rng::{self, FastU32};
 use crate::visualizers::{VisualizerArgs, VisualizerConfig};
 
@@ -117,16 +118,23 @@ impl Game {
         pix.clear();
         pix.mixerd();
 
-        pix.color(0xFF_FF_FF);
+        let theme = pix.theme();
+
+        pix.color(theme.foreground(0xFF_FF_FF));
 
         for p in self.positions.iter() {
             pix.rect(*p, Self::UNIT as usize, Self::UNIT as usize);
         }
 
-        pix.color(0xFF_00_00);
+        pix.color(theme.accent(1, 0xFF_00_00));
 
         pix.rect(self.apple, Self::UNIT as usize, Self::UNIT as usize);
 
+        pix.color(theme.muted(0x80_80_80));
+        pix.font(FONT_4X6);
+        pix.text_align(TextAlign::Left);
+        pix.text(P2(1, 1), &self.score.to_string());
+
         if self.lose != 0 {
             pix.fade(128);
         }
@@ -147,6 +155,7 @@ impl crate::visualizers::Visualizer for Snake {
         VisualizerConfig {
             normalize: true,
             nosleep: true,
+            effects: &[],
         }
     }
 
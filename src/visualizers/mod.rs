@@ -4,8 +4,8 @@ mod misc;
 
 use crate::{
     audio::AudioBuffer,
-    data::{DEFAULT_VIS_SWITCH_DURATION, KeyInput, log},
-    graphics::Painter,
+    data::{DEFAULT_VIS_SWITCH_DURATION, KeyInput, VIS_NAME_DURATION, log},
+    graphics::{Painter, postfx::PostEffect},
     visualizers::{
         classic::{
             bars::{Bars, BarsCircle},
@@ -18,14 +18,19 @@ use crate::{
             vol_sweeper::VolSweeper,
             wave::Wave,
         },
-        milk::rain::Rain,
+        milk::{rain::Rain, tunnel::Tunnel},
         misc::{example::Example, snake::Snake},
     },
 };
 
 #[allow(dead_code)]
-pub struct VisualizerArgs<'a> {
-    pub pix: &'a mut Painter<'a>,
+pub struct VisualizerArgs<'a, 'b> {
+    pub pix: &'a mut Painter<'b>,
+
+    /// Cleared every frame and drawn above `pix`,
+    /// for things that should not persist.
+    pub overlay: &'a mut Painter<'b>,
+
     pub stream: &'a mut AudioBuffer,
     pub keys: &'a KeyInput,
     pub delta: f32,
@@ -35,6 +40,9 @@ pub struct VisualizerArgs<'a> {
 pub struct VisualizerConfig {
     pub normalize: bool,
     pub nosleep: bool,
+
+    /// Post effects run before the ones given with `--effect`.
+    pub effects: &'static [PostEffect],
 }
 
 pub trait Visualizer {
@@ -42,6 +50,7 @@ pub trait Visualizer {
         VisualizerConfig {
             normalize: true,
             nosleep: false,
+            effects: &[],
         }
     }
 
@@ -61,6 +70,9 @@ pub struct VisList {
     list: Vec<Box<dyn Visualizer>>,
     index: usize,
     next_update: Instant,
+    switched_at: Instant,
+    /// Index of the visualizer switched away from, until taken.
+    outgoing: Option<usize>,
     pub auto_switch: bool,
 }
 
@@ -83,6 +95,7 @@ impl VisList {
                 Box::new(VolSweeper::default()),
                 Box::new(Wave {}),
                 Box::new(Rain::default()),
+                Box::new(Tunnel::default()),
                 Box::new(Snake::default()),
                 // your visualizers go here.
                 // they can be placed in any order.
@@ -94,12 +107,15 @@ impl VisList {
             ],
             index: 0,
             next_update: Instant::now() + DEFAULT_VIS_SWITCH_DURATION,
+            switched_at: Instant::now(),
+            outgoing: None,
             auto_switch: true,
         }
     }
 
     fn reset_timer(&mut self) {
-        self.next_update = Instant::now() + DEFAULT_VIS_SWITCH_DURATION;
+        self.switched_at = Instant::now();
+        self.next_update = self.switched_at + DEFAULT_VIS_SWITCH_DURATION;
     }
 
     fn log(&self) {
@@ -108,6 +124,7 @@ impl VisList {
 
     pub fn next(&mut self) {
         self.list[self.index].defocus();
+        self.outgoing = Some(self.index);
 
         self.index += 1;
 
@@ -123,6 +140,7 @@ impl VisList {
 
     pub fn prev(&mut self) {
         self.list[self.index].defocus();
+        self.outgoing = Some(self.index);
 
         if self.index == 0 {
             self.index = self.list.len();
@@ -141,17 +159,52 @@ impl VisList {
             let now = Instant::now();
             if now >= self.next_update {
                 self.next();
-                self.next_update = now + DEFAULT_VIS_SWITCH_DURATION;
                 return Some(self.get().config());
             }
         }
         None
     }
 
+    /// Name of the current visualizer if it was switched
+    /// to within the last `VIS_NAME_DURATION`.
+    pub fn recent_name(&self) -> Option<&'static str> {
+        (self.switched_at.elapsed() < VIS_NAME_DURATION).then(|| self.list[self.index].name())
+    }
+
+    /// Shows the name again, without touching the auto switch timer.
+    pub fn show_name(&mut self) {
+        self.switched_at = Instant::now();
+    }
+
+    /// Starts at the visualizer at `index`, wrapping around.
+    pub fn select(&mut self, index: usize) {
+        self.index = index % self.list.len();
+        self.list[self.index].focus();
+        self.reset_timer();
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.list[self.index].name()
+    }
+
     pub fn get(&mut self) -> &mut dyn Visualizer {
         self.list[self.index].as_mut()
     }
 
+    pub fn names(&self) -> Vec<&'static str> {
+        self.list.iter().map(|v| v.name()).collect()
+    }
+
+    pub fn get_at(&mut self, index: usize) -> &mut dyn Visualizer {
+        self.list[index].as_mut()
+    }
+
+    /// The visualizer switched away from since the last call, unless
+    /// it was switched back to.
+    pub fn take_outgoing(&mut self) -> Option<usize> {
+        self.outgoing.take().filter(|&i| i != self.index)
+    }
+
     pub fn select_by_name(&mut self, name: &str) {
         if let Some(i) = self
             .list
@@ -160,6 +213,7 @@ impl VisList {
         {
             self.index = i;
             self.list[self.index].focus();
+            self.reset_timer();
             return;
         }
 
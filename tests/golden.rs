@@ -0,0 +1,21 @@
+//! Runs `coffeevis golden` against the references in `tests/golden`.
+//! The fast feature changes what gets drawn, so it is left out there.
+#![cfg(not(feature = "fast"))]
+
+use std::process::Command;
+
+#[test]
+fn golden() {
+    let output = Command::new(env!("CARGO_BIN_EXE_coffeevis"))
+        .arg("golden")
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("failed to run coffeevis");
+
+    assert!(
+        output.status.success(),
+        "golden images differ, see tests/golden/failures\n{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}